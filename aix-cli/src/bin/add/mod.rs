@@ -0,0 +1,165 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{env::current_dir, path::PathBuf};
+
+use aix::config::{Config, SyncMode};
+use aix::project::extras::{
+    self,
+    ci::{CiOption, CI},
+    release::ReleaseTool,
+    ExtraChanges, ProjectExtra,
+};
+use aix::project::Project;
+use clap::{value_parser, ArgAction, Args};
+use log::{info, warn};
+
+/// Adds an extra to an existing project based on provided arguments.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used to access application configuration and resources.
+/// - `args`: An instance of `AddExtraArgs` containing the extra to add and its options.
+///
+/// # Returns
+///
+/// - `Ok(())` if the extra was added successfully.
+/// - `Err` if any error occurs, such as files modified since the project was generated.
+pub async fn run(app_handle: &Config, args: &AddExtraArgs) -> aix::Result<()> {
+    let defaults = app_handle.user_config();
+
+    let project_dir = match &args.dir {
+        Some(dir) => dir.clone(),
+        None => current_dir()?,
+    };
+    // Projects generated by earlier versions of aix record no project.
+    let mut project = match Project::load(&project_dir).await? {
+        Some(project) => project,
+        None => Project::detect(&project_dir).await?,
+    };
+
+    // The options of extras default to the configured ones.
+    let extra = match &args.extra {
+        ProjectExtra::CI(_) => {
+            let ci = args.provider.clone().or(defaults.ci.clone());
+            project.ci_options = match args.ci_options.is_empty() {
+                true => defaults.ci_options.clone(),
+                false => args.ci_options.clone(),
+            };
+            ProjectExtra::CI(ci.unwrap_or_default())
+        }
+        ProjectExtra::Release(_) => {
+            let tool = args.tool.clone().or(defaults.release.clone());
+            ProjectExtra::Release(tool.unwrap_or_default())
+        }
+        extra => extra.clone(),
+    };
+
+    if args.provider.is_some() && !matches!(extra, ProjectExtra::CI(_)) {
+        warn!("--provider only applies to the ci extra");
+    }
+    if !args.ci_options.is_empty() && !matches!(extra, ProjectExtra::CI(_)) {
+        warn!("--ci-option only applies to the ci extra");
+    }
+    if args.tool.is_some() && !matches!(extra, ProjectExtra::Release(_)) {
+        warn!("--tool only applies to the release extra");
+    }
+
+    let sync_mode = match (args.refresh, args.offline) {
+        (true, _) => SyncMode::Refresh,
+        (_, true) => SyncMode::Offline,
+        _ => SyncMode::Lazy,
+    };
+
+    let changes =
+        extras::add_extra(app_handle, &project, extra.clone(), sync_mode, args.force).await?;
+    print_changes(&extra, &changes);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AddExtraArgs {
+    /// The extra to add (docker, ci, release or updates).
+    #[arg(value_name = "EXTRA", value_parser = value_parser!(ProjectExtra))]
+    pub extra: ProjectExtra,
+
+    /// The CI provider of the ci extra (github, gitlab, forgejo or woodpecker).
+    /// Defaults to the configured provider.
+    #[arg(long, value_name = "PROVIDER", value_parser = value_parser!(CI), verbatim_doc_comment)]
+    pub provider: Option<CI>,
+
+    /// Adds optional parts to the CI pipeline of the ci extra
+    /// (msrv, coverage, audit, multi-os or release).
+    #[arg(
+        long = "ci-option",
+        value_name = "OPTION",
+        value_delimiter = ',',
+        value_parser = value_parser!(CiOption),
+        verbatim_doc_comment
+    )]
+    pub ci_options: Vec<CiOption>,
+
+    /// The release tool of the release extra (release-plz or cargo-release).
+    /// Defaults to the configured release tool.
+    #[arg(long, value_name = "TOOL", value_parser = value_parser!(ReleaseTool), verbatim_doc_comment)]
+    pub tool: Option<ReleaseTool>,
+
+    /// The directory of the project.
+    /// Defaults to the current working directory if not specified.
+    #[arg(
+        short = 'd',
+        long = "dir",
+        value_name = "DIRECTORY",
+        verbatim_doc_comment
+    )]
+    pub dir: Option<PathBuf>,
+
+    /// Overwrites files that were modified since the project was generated.
+    #[arg(long, action(ArgAction::SetTrue), default_value_t = false)]
+    pub force: bool,
+
+    /// Pulls the latest project templates before adding the extra.
+    #[arg(
+        long,
+        action(ArgAction::SetTrue),
+        default_value_t = false,
+        conflicts_with = "offline"
+    )]
+    pub refresh: bool,
+
+    /// Adds the extra from previously pulled templates
+    /// without accessing the network.
+    #[arg(
+        long,
+        action(ArgAction::SetTrue),
+        default_value_t = false,
+        verbatim_doc_comment
+    )]
+    pub offline: bool,
+}
+
+fn print_changes(extra: &ProjectExtra, changes: &ExtraChanges) {
+    let files = changes
+        .created
+        .iter()
+        .map(|path| format!("\x1b[38;2;46;111;64mADD\x1b[0m {}", path.display()))
+        .chain(
+            changes
+                .updated
+                .iter()
+                .map(|path| format!("\x1b[38;2;46;86;111mUPDATE\x1b[0m {}", path.display())),
+        )
+        .chain(
+            changes
+                .overwritten
+                .iter()
+                .map(|path| format!("\x1b[38;2;150;96;30mOVERWRITE\x1b[0m {}", path.display())),
+        )
+        .reduce(|prev: String, curr: String| format!("{prev}\n   {curr}"));
+
+    match files {
+        Some(files) => info!("Successfully added the {extra} extra\n   {files}"),
+        None => info!("The {extra} extra is already set up"),
+    }
+}
@@ -1,10 +1,16 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+mod add;
+mod config;
 mod new;
+mod registry;
+mod remove;
+mod templates;
+mod update;
 use new as new_project;
 
-use aix::config::Config;
+use aix::config::{Config, UserConfig};
 use aix::git::GitRepository;
 use log::LevelFilter;
 
@@ -19,10 +25,16 @@ async fn main() -> aix::Result<()> {
     std::env::set_var("RUST_LOG", log_level.to_string());
     aix::log::init_logger(Some(log_level));
 
-    let handle = get_app_handle()?;
+    let handle = get_app_handle(&cli.global_args.config).await?;
 
     match cli.command {
         Command::NewProject(args) => new::run(&handle, &args).await?,
+        Command::Add(args) => add::run(&handle, &args).await?,
+        Command::Remove(args) => remove::run(&handle, &args).await?,
+        Command::Update(args) => update::run(&handle, &args).await?,
+        Command::Templates(args) => templates::run(&handle, &args).await?,
+        Command::Registry(args) => registry::run(&handle, &args).await?,
+        Command::Config(args) => config::run(&handle, &args).await?,
     };
 
     Ok(())
@@ -45,6 +57,30 @@ pub enum Command {
     /// Generates a new Rust + Actix starter project.
     #[command(name = "new")]
     NewProject(new_project::NewProjectArgs),
+
+    /// Adds an extra to an existing project.
+    #[command(name = "add")]
+    Add(add::AddExtraArgs),
+
+    /// Removes an extra from an existing project.
+    #[command(name = "remove")]
+    Remove(remove::RemoveExtraArgs),
+
+    /// Updates an existing project to the latest templates.
+    #[command(name = "update")]
+    Update(update::UpdateProjectArgs),
+
+    /// Inspects and manages the local project template store.
+    #[command(name = "templates")]
+    Templates(templates::TemplatesArgs),
+
+    /// Manages the registries project templates are pulled from.
+    #[command(name = "registry")]
+    Registry(registry::RegistryArgs),
+
+    /// Inspects and edits the configuration file.
+    #[command(name = "config")]
+    Config(config::ConfigArgs),
 }
 
 #[derive(Args)]
@@ -52,9 +88,13 @@ pub struct GlobalArgs {
     /// Sets the level of verbosity (-v, -vv, -vvv)
     #[arg(short, long, global = true, action = clap::ArgAction::Count)]
     pub verbose: u8,
+
+    /// Overrides a configuration option (e.g. -c rust_version=1.80)
+    #[arg(short = 'c', long = "config", global = true, value_name = "KEY=VALUE", value_parser = parse_config_override)]
+    pub config: Vec<(String, String)>,
 }
 
-fn get_app_handle() -> aix::Result<Config> {
+async fn get_app_handle(config_overrides: &[(String, String)]) -> aix::Result<Config> {
     let app_name = std::env::current_exe()?;
     let app_name = app_name.file_name().unwrap().to_string_lossy();
 
@@ -62,10 +102,24 @@ fn get_app_handle() -> aix::Result<Config> {
         url: "git@github.com:ekkolon/aix.git".into(),
         directory: "templates".into(),
         branch: "main".into(),
+        checksums_sha256: None,
     };
 
     let app_handle = Config::new(&app_name, git_repo)?;
-    Ok(app_handle)
+
+    let mut flag = UserConfig::default();
+    for (key, value) in config_overrides {
+        flag.set(key, value)?;
+    }
+
+    app_handle.load_user_config(flag).await
+}
+
+fn parse_config_override(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got {value}"))
 }
 
 fn get_log_level_filter(verbosity: u8) -> LevelFilter {
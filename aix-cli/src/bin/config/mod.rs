@@ -0,0 +1,116 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use aix::config::{Config, UserConfig};
+use clap::{ArgAction, Args, Subcommand};
+use log::info;
+
+/// Runs a `config` subcommand to inspect and edit the user's configuration.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used to access application configuration and resources.
+/// - `args`: An instance of `ConfigArgs` containing the subcommand to run.
+///
+/// # Returns
+///
+/// - `Ok(())` if the subcommand completes successfully.
+/// - `Err` if any error occurs, such as an unknown option or file system errors.
+pub async fn run(app_handle: &Config, args: &ConfigArgs) -> aix::Result<()> {
+    match &args.command {
+        ConfigCommand::Get { key } => {
+            if let Some(value) = effective_value(app_handle, key)? {
+                println!("{value}");
+            }
+            Ok(())
+        }
+        ConfigCommand::Set { key, value } => {
+            let mut config = UserConfig::load(app_handle.config_file()).await?;
+            config.set(key, value)?;
+            config.save(app_handle.config_file()).await?;
+            info!("Set {key} to {value}");
+            Ok(())
+        }
+        ConfigCommand::Unset { key } => {
+            let mut config = UserConfig::load(app_handle.config_file()).await?;
+            config.unset(key)?;
+            config.save(app_handle.config_file()).await?;
+            info!("Unset {key}");
+            Ok(())
+        }
+        ConfigCommand::List { show_origin } => list(app_handle, *show_origin),
+        ConfigCommand::Path => {
+            println!("{}", app_handle.config_file().display());
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Prints the value of an option.
+    Get {
+        /// The name of the option (e.g. rust_version).
+        key: String,
+    },
+
+    /// Sets an option in the configuration file.
+    Set {
+        /// The name of the option (e.g. rust_version).
+        key: String,
+
+        /// The value of the option.
+        /// Multiple values (e.g. of extras) are separated by commas.
+        #[arg(verbatim_doc_comment)]
+        value: String,
+    },
+
+    /// Removes an option from the configuration file.
+    Unset {
+        /// The name of the option (e.g. rust_version).
+        key: String,
+    },
+
+    /// Lists the options and their values.
+    List {
+        /// Shows where the value of each option comes from
+        /// (default, file, env or flag).
+        #[arg(long, action(ArgAction::SetTrue), verbatim_doc_comment)]
+        show_origin: bool,
+    },
+
+    /// Prints the path of the configuration file.
+    Path,
+}
+
+/// Gets the value used for the option `key`, falling back to its default value.
+fn effective_value(app_handle: &Config, key: &str) -> aix::Result<Option<String>> {
+    match app_handle.user_config().get(key)? {
+        Some(value) => Ok(Some(value)),
+        None => UserConfig::default_value(key),
+    }
+}
+
+fn list(app_handle: &Config, show_origin: bool) -> aix::Result<()> {
+    let layers = app_handle.user_config_layers();
+
+    for key in UserConfig::KEYS {
+        let Some(value) = effective_value(app_handle, key)? else {
+            continue;
+        };
+
+        if show_origin {
+            println!("{:7}  {key}={value}", layers.origin(key)?.to_string());
+        } else {
+            println!("{key}={value}");
+        }
+    }
+
+    Ok(())
+}
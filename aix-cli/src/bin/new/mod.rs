@@ -1,11 +1,21 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::{env::current_dir, path::PathBuf};
+mod wizard;
 
-use aix::config::Config;
-use aix::project::extras::ci::CI;
+use std::io::IsTerminal;
+use std::{collections::BTreeMap, env::current_dir, path::PathBuf};
+
+use aix::answers;
+use aix::config::{Config, SyncMode, TemplateRef};
+use aix::project::extras::{
+    ci::{CiOption, CI},
+    release::ReleaseTool,
+    ProjectExtra,
+};
 use aix::project::{Project, ProjectBuilder, ProjectType};
+use aix::prompt::Prompter;
+use aix::template::archive::ArchiveSource;
 use clap::{value_parser, ArgAction, Args, Parser};
 use log::info;
 use serde::{Deserialize, Serialize};
@@ -39,34 +49,187 @@ use tokio::fs;
 ///     root_dir: None,
 ///     project_kind: ProjectKind { standalone: true, ..Default::default() },
 ///     setup_ci: Some(CI::Github),
-///     setup_docker: true,
+///     setup_docker: Some(true),
 /// };
 /// run(&app_handle, &args).await?;
 /// ```
 pub async fn run(app_handle: &Config, args: &NewProjectArgs) -> aix::Result<()> {
+    // Options passed on the command line take precedence over the answers file.
+    let mut args = match &args.from {
+        Some(path) => {
+            let answers: NewProjectArgs = answers::load(path).await?;
+            if args.name.is_empty() && answers.name.is_empty() {
+                return Err(aix::Error::InvalidAnswers {
+                    path: path.clone(),
+                    message: "missing field `name`".into(),
+                });
+            }
+            args.clone().with_answers(answers)
+        }
+        None => args.clone(),
+    };
+
+    // Ask for the options that were not passed if there is no project name.
+    let mut prompter = None;
+    if args.name.is_empty() {
+        if !std::io::stdin().is_terminal() {
+            return Err(aix::Error::MissingProjectName);
+        }
+        let prompter = prompter.insert(Prompter::stdio());
+        args = wizard::prompt_args(prompter, app_handle.user_config(), args)?;
+    }
+
+    // Answers files and the wizard decide on all extras, so the extras of the
+    // configuration only apply to options passed on the command line.
+    let explicit_extras = args.from.is_some() || prompter.is_some();
+
+    let sync_mode = match (args.refresh.unwrap_or(false), args.offline.unwrap_or(false)) {
+        (true, _) => SyncMode::Refresh,
+        (_, true) => SyncMode::Offline,
+        _ => SyncMode::Lazy,
+    };
+
+    let template = args
+        .template
+        .clone()
+        .or(app_handle.user_config().template.clone());
+    let mut archive_template = None;
+    let mut template_ref = None;
+    if let Some(template) = &template {
+        if is_archive(template) {
+            let source: ArchiveSource = template.parse()?;
+            let template = app_handle
+                .archive_template(&source, args.template_sha256.as_deref())
+                .await?;
+            archive_template = Some(template);
+        } else {
+            let reference: TemplateRef = template.parse()?;
+            template_ref = Some(reference);
+        }
+    }
+
+    if let Some(prompter) = &mut prompter {
+        let typ = match args.project_kind.standalone || args.project_kind.workspace {
+            true => ProjectType::from(&args.project_kind),
+            false => app_handle.user_config().kind.unwrap_or_default(),
+        };
+        let variables = wizard::template_variables(
+            app_handle,
+            archive_template.as_ref(),
+            template_ref.as_ref(),
+            typ,
+            sync_mode,
+        )
+        .await?;
+        wizard::prompt_variables(prompter, variables, &mut args.variables)?;
+    }
+
     let NewProjectArgs {
         name,
         root_dir,
         project_kind,
         setup_ci,
+        ci_options,
         setup_docker,
-    } = args;
+        setup_release,
+        setup_updates,
+        author,
+        license,
+        rust_version,
+        variables,
+        save_answers,
+        ..
+    } = &args;
 
-    let project_type = ProjectType::from(project_kind);
     let project_src_root = build_project_out_dir(name, root_dir).await?;
 
-    let project = ProjectBuilder::new(app_handle.clone())
-        .typ(project_type)
+    // The builder starts from the defaults of the user's configuration file,
+    // which are overridden by the options that were passed.
+    let mut builder = ProjectBuilder::new(app_handle.clone())
         .name(name)
         .src_root(project_src_root)
-        .setup_ci(setup_ci.is_some())
-        .setup_docker(*setup_docker)
-        .build()
-        .await?;
+        .sync_mode(sync_mode);
+
+    if project_kind.standalone || project_kind.workspace {
+        builder = builder.typ(ProjectType::from(project_kind));
+    }
+    if explicit_extras {
+        builder = builder
+            .setup_ci(setup_ci.clone())
+            .ci_options(ci_options.clone())
+            .setup_docker(setup_docker.unwrap_or(false))
+            .setup_release(setup_release.clone())
+            .setup_updates(setup_updates.unwrap_or(false));
+    }
+    if setup_ci.is_some() {
+        builder = builder.setup_ci(setup_ci.clone());
+    }
+    if !ci_options.is_empty() {
+        builder = builder.ci_options(ci_options.clone());
+    }
+    if let Some(setup_docker) = setup_docker {
+        builder = builder.setup_docker(*setup_docker);
+    }
+    if setup_release.is_some() {
+        builder = builder.setup_release(setup_release.clone());
+    }
+    if let Some(setup_updates) = setup_updates {
+        builder = builder.setup_updates(*setup_updates);
+    }
+    if let Some(author) = author {
+        builder = builder.author(author);
+    }
+    if let Some(license) = license {
+        builder = builder.license(license);
+    }
+    if let Some(rust_version) = rust_version {
+        builder = builder.rust_version(rust_version);
+    }
+    for (name, value) in variables {
+        builder = builder.variable(name, value);
+    }
+    if let Some(template) = archive_template {
+        builder = builder.template(template);
+    }
+    if let Some(reference) = template_ref {
+        builder = builder.template_ref(reference);
+    }
+
+    let project = builder.build().await?;
 
     let project_files = project.compile().await?;
     print_new_project_files(&project, &project_files);
 
+    if let Some(path) = save_answers {
+        // Record the answers resolved from the configuration, so replaying them
+        // does not depend on the configuration of the current user.
+        let answers = NewProjectArgs {
+            project_kind: ProjectKind {
+                standalone: *project.typ() == ProjectType::Standalone,
+                workspace: *project.typ() == ProjectType::Workspace,
+            },
+            setup_ci: project.ci().cloned(),
+            ci_options: match project.ci() {
+                Some(_) => project.ci_options.clone(),
+                None => vec![],
+            },
+            setup_docker: Some(project.has_extra(&ProjectExtra::Docker)),
+            setup_release: project.release().cloned(),
+            setup_updates: Some(project.has_extra(&ProjectExtra::Updates)),
+            template,
+            author: project.author.clone(),
+            license: project.license.clone(),
+            rust_version: Some(project.rust_version().to_string()),
+            variables: project.variables.clone(),
+            // How templates are synced is decided for each run.
+            refresh: None,
+            offline: None,
+            ..args.clone()
+        };
+        answers::save(path, &answers).await?;
+        info!("Saved answers to {}", path.display());
+    }
+
     Ok(())
 }
 
@@ -75,6 +238,8 @@ pub async fn run(app_handle: &Config, args: &NewProjectArgs) -> aix::Result<()>
 #[serde(deny_unknown_fields, default)]
 pub struct NewProjectArgs {
     /// The name of the project.
+    /// Asks for the options of the project if omitted in an interactive terminal.
+    #[arg(default_value = "", hide_default_value = true, verbatim_doc_comment)]
     pub name: String,
 
     #[clap(flatten)]
@@ -88,23 +253,184 @@ pub struct NewProjectArgs {
         value_name = "DIRECTORY",
         verbatim_doc_comment
     )]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub root_dir: Option<PathBuf>,
 
     /// Specifies a Continuous Integration provider to set up
-    /// for the project (e.g., GitHub or GitLab).
+    /// for the project (github, gitlab, forgejo or woodpecker).
     #[arg(long, value_name = "PROVIDER", value_parser = value_parser!(CI), verbatim_doc_comment)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub setup_ci: Option<CI>,
 
+    /// Adds optional parts to the CI pipeline
+    /// (msrv, coverage, audit, multi-os or release).
+    #[arg(
+        long = "ci-option",
+        value_name = "OPTION",
+        value_delimiter = ',',
+        value_parser = value_parser!(CiOption),
+        verbatim_doc_comment
+    )]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ci_options: Vec<CiOption>,
+
     /// Indicates whether to add a Dockerfile to the generated project.
-    /// Defaults to `false`
-    ///
+    /// Defaults to `false`, unless the docker extra is configured.
+    /// Pass `--setup-docker=false` to override the configuration or answers file.
     #[arg(
         long,
-        action(ArgAction::SetTrue),
-        default_value_t = false,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        verbatim_doc_comment
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_docker: Option<bool>,
+
+    /// Specifies a release tool to set up for the project
+    /// (release-plz or cargo-release), which adds a changelog and
+    /// release jobs to the CI pipeline.
+    #[arg(long, value_name = "TOOL", value_parser = value_parser!(ReleaseTool), verbatim_doc_comment)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_release: Option<ReleaseTool>,
+
+    /// Indicates whether to add the configuration of a bot updating dependencies,
+    /// which is Dependabot for GitHub Actions and Renovate otherwise.
+    /// Defaults to `false`, unless the updates extra is configured.
+    /// Pass `--setup-updates=false` to override the configuration or answers file.
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        verbatim_doc_comment
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_updates: Option<bool>,
+
+    /// Pulls the latest project templates before generating the project.
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        conflicts_with = "offline"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh: Option<bool>,
+
+    /// Generates the project from previously pulled templates
+    /// without accessing the network.
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
         verbatim_doc_comment
     )]
-    pub setup_docker: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline: Option<bool>,
+
+    /// Generates the project from a registry template (e.g. company/api),
+    /// or from a tar.gz or zip archive located at a local path or an HTTP(S) URL.
+    #[arg(long, value_name = "TEMPLATE", verbatim_doc_comment)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// The expected SHA-256 checksum of the template archive.
+    #[arg(long, value_name = "SHA256", requires = "template")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_sha256: Option<String>,
+
+    /// The author of the project (e.g. "Jane Doe <jane@example.com>").
+    #[arg(long, value_name = "AUTHOR")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// The license of the project (e.g. "MIT OR Apache-2.0").
+    #[arg(long, value_name = "LICENSE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// The minimum supported Rust version of the project.
+    #[arg(long, value_name = "VERSION")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rust_version: Option<String>,
+
+    /// The values of template variables, keyed by name.
+    #[arg(skip)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+
+    /// Reads the answers to generate the project from a JSON, TOML or YAML file.
+    /// Options passed on the command line take precedence over the file.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    #[serde(skip)]
+    pub from: Option<PathBuf>,
+
+    /// Writes the answers used to generate the project to a JSON, TOML or YAML file,
+    /// which can be replayed with --from.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    #[serde(skip)]
+    pub save_answers: Option<PathBuf>,
+}
+
+impl NewProjectArgs {
+    /// Fills in the options that were not passed with the values of `answers`.
+    fn with_answers(self, answers: NewProjectArgs) -> Self {
+        let has_kind = self.project_kind.standalone || self.project_kind.workspace;
+        let has_template = self.template.is_some();
+        let has_sync_mode = self.refresh.is_some() || self.offline.is_some();
+
+        Self {
+            name: match self.name.is_empty() {
+                true => answers.name,
+                false => self.name,
+            },
+            project_kind: match has_kind {
+                true => self.project_kind,
+                false => answers.project_kind,
+            },
+            root_dir: self.root_dir.or(answers.root_dir),
+            setup_ci: self.setup_ci.or(answers.setup_ci),
+            ci_options: match self.ci_options.is_empty() {
+                true => answers.ci_options,
+                false => self.ci_options,
+            },
+            setup_docker: self.setup_docker.or(answers.setup_docker),
+            setup_release: self.setup_release.or(answers.setup_release),
+            setup_updates: self.setup_updates.or(answers.setup_updates),
+            // Refreshing and working offline are a single choice.
+            refresh: match has_sync_mode {
+                true => self.refresh,
+                false => answers.refresh,
+            },
+            offline: match has_sync_mode {
+                true => self.offline,
+                false => answers.offline,
+            },
+            template: self.template.or(answers.template),
+            // The checksum belongs to the template it was given with.
+            template_sha256: match has_template {
+                true => self.template_sha256,
+                false => answers.template_sha256,
+            },
+            author: self.author.or(answers.author),
+            license: self.license.or(answers.license),
+            rust_version: self.rust_version.or(answers.rust_version),
+            variables: answers
+                .variables
+                .into_iter()
+                .chain(self.variables)
+                .collect(),
+            from: self.from,
+            save_answers: self.save_answers,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, Args)]
@@ -143,6 +469,16 @@ impl From<&ProjectKind> for ProjectType {
     }
 }
 
+/// Checks whether `template` refers to a template archive rather than a registry template.
+fn is_archive(template: &str) -> bool {
+    let is_url = template.starts_with("http://") || template.starts_with("https://");
+    let has_extension = [".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .any(|ext| template.ends_with(ext));
+
+    is_url || has_extension || PathBuf::from(template).is_file()
+}
+
 fn print_new_project_files(project: &Project, file_list: &[String]) {
     let file_list_formatted = file_list
         .iter()
@@ -174,3 +510,48 @@ async fn build_project_out_dir(name: &str, root_dir: &Option<PathBuf>) -> aix::R
 
     Ok(out_dir)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_answers_prefers_passed_options() {
+        let answers = NewProjectArgs {
+            name: "my_api".into(),
+            setup_docker: Some(true),
+            setup_updates: Some(true),
+            offline: Some(true),
+            author: Some("Jane Doe".into()),
+            ..Default::default()
+        };
+
+        // Options that were not passed use the answers.
+        let args = NewProjectArgs::parse_from(["new"]).with_answers(answers.clone());
+        assert_eq!(args.name, "my_api");
+        assert_eq!(args.setup_docker, Some(true));
+        assert_eq!(args.setup_updates, Some(true));
+        assert_eq!(args.offline, Some(true));
+
+        let args = NewProjectArgs::parse_from([
+            "new",
+            "--setup-docker=false",
+            "--setup-updates=false",
+            "--offline=false",
+        ])
+        .with_answers(answers);
+        assert_eq!(args.setup_docker, Some(false));
+        assert_eq!(args.setup_updates, Some(false));
+        assert_eq!(args.offline, Some(false));
+        assert_eq!(args.author.as_deref(), Some("Jane Doe"));
+
+        // Passing either sync option ignores both from the answers.
+        let answers = NewProjectArgs {
+            refresh: Some(true),
+            ..Default::default()
+        };
+        let args = NewProjectArgs::parse_from(["new", "--offline"]).with_answers(answers);
+        assert_eq!(args.refresh, None);
+        assert_eq!(args.offline, Some(true));
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+use aix::config::{Config, SyncMode, TemplateRef, UserConfig};
+use aix::project::extras::{ci::CiOption, ProjectExtra};
+use aix::project::{ProjectType, BUILTIN_VARIABLES, DEFAULT_RUST_VERSION};
+use aix::prompt::Prompter;
+use aix::template::{Template, TemplateVariable};
+
+use super::{NewProjectArgs, ProjectKind};
+
+/// Asks for the name of the project and the options that were not passed,
+/// suggesting the defaults of the user's configuration.
+pub fn prompt_args<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    defaults: &UserConfig,
+    mut args: NewProjectArgs,
+) -> aix::Result<NewProjectArgs> {
+    args.name = prompter.text("Project name", None, validate_name)?;
+
+    if !args.project_kind.standalone && !args.project_kind.workspace {
+        let kind = defaults.kind.unwrap_or_default();
+        let kind: ProjectType =
+            prompter.parse("Project kind (standalone, workspace)", Some(&kind))?;
+        args.project_kind = ProjectKind {
+            standalone: kind == ProjectType::Standalone,
+            workspace: kind == ProjectType::Workspace,
+        };
+    }
+
+    if args.setup_ci.is_none() {
+        let default = defaults
+            .extras
+            .iter()
+            .any(|extra| matches!(extra, ProjectExtra::CI(_)));
+        if prompter.confirm("Set up CI?", default)? {
+            let ci = defaults.ci.clone().unwrap_or_default();
+            args.setup_ci = Some(prompter.parse("CI provider", Some(&ci))?);
+
+            if args.ci_options.is_empty() {
+                let default = defaults
+                    .ci_options
+                    .iter()
+                    .map(CiOption::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let options = prompter.text(
+                    "CI options (msrv, coverage, audit, multi-os, release)",
+                    (!default.is_empty()).then_some(default.as_str()),
+                    |options| parse_ci_options(options).map(|_| ()),
+                )?;
+                args.ci_options = parse_ci_options(&options).unwrap();
+            }
+        }
+    }
+
+    if args.setup_docker.is_none() {
+        let default = defaults.extras.contains(&ProjectExtra::Docker);
+        args.setup_docker = Some(prompter.confirm("Add a Dockerfile?", default)?);
+    }
+
+    if args.setup_release.is_none() {
+        let default = defaults
+            .extras
+            .iter()
+            .any(|extra| matches!(extra, ProjectExtra::Release(_)));
+        if prompter.confirm("Set up release automation?", default)? {
+            let tool = defaults.release.clone().unwrap_or_default();
+            args.setup_release =
+                Some(prompter.parse("Release tool (release-plz, cargo-release)", Some(&tool))?);
+        }
+    }
+
+    if args.setup_updates.is_none() {
+        let default = defaults.extras.contains(&ProjectExtra::Updates);
+        args.setup_updates = Some(prompter.confirm("Set up dependency updates?", default)?);
+    }
+
+    if args.author.is_none() {
+        let author = prompter.text("Author", defaults.author.as_deref(), |_| Ok(()))?;
+        args.author = (!author.is_empty()).then_some(author);
+    }
+
+    if args.license.is_none() {
+        let license = prompter.text("License", defaults.license.as_deref(), |_| Ok(()))?;
+        args.license = (!license.is_empty()).then_some(license);
+    }
+
+    if args.rust_version.is_none() {
+        let default = defaults
+            .rust_version
+            .as_deref()
+            .unwrap_or(DEFAULT_RUST_VERSION);
+        let rust_version = prompter.text("Rust version", Some(default), validate_rust_version)?;
+        args.rust_version = Some(rust_version);
+    }
+
+    Ok(args)
+}
+
+/// Asks for the values of the template `variables` that are neither provided by the
+/// project itself nor set in `values`, suggesting the defaults of the template manifest.
+pub fn prompt_variables<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    variables: BTreeMap<String, TemplateVariable>,
+    values: &mut BTreeMap<String, String>,
+) -> aix::Result<()> {
+    for (name, variable) in variables {
+        if BUILTIN_VARIABLES.contains(&name.as_str()) || values.contains_key(&name) {
+            continue;
+        }
+
+        let question = match &variable.description {
+            Some(description) => format!("{name} ({description})"),
+            None => name.clone(),
+        };
+        let value = prompter.text(&question, variable.default.as_deref(), |_| Ok(()))?;
+        values.insert(name, value);
+    }
+
+    Ok(())
+}
+
+/// Collects the variables of the template the project will be generated from,
+/// which is either `template`, the template referenced by `reference`, or the
+/// template for the project type `typ`.
+pub async fn template_variables(
+    app_handle: &Config,
+    template: Option<&Template>,
+    reference: Option<&TemplateRef>,
+    typ: ProjectType,
+    sync_mode: SyncMode,
+) -> aix::Result<BTreeMap<String, TemplateVariable>> {
+    if let Some(template) = template {
+        return template.variables().await;
+    }
+
+    let (store, name) = match reference {
+        Some(reference) => (
+            app_handle.registry(&reference.registry).await?,
+            reference.name.clone(),
+        ),
+        None => (app_handle.clone(), typ.to_string()),
+    };
+
+    store.ensure_store(sync_mode).await?;
+    let _lock = store.lock_store_shared().await?;
+    store.template(&name).await?.variables().await
+}
+
+/// Parses a comma-separated list of CI options.
+fn parse_ci_options(options: &str) -> Result<Vec<CiOption>, String> {
+    options
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(|option| option.parse().map_err(|err: aix::Error| err.to_string()))
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let is_valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+
+    match is_valid {
+        true => Ok(()),
+        false => Err(
+            "The name must start with a letter and only contain letters, digits, `-` and `_`"
+                .into(),
+        ),
+    }
+}
+
+fn validate_rust_version(version: &str) -> Result<(), String> {
+    let parts: Vec<_> = version.split('.').collect();
+    let is_valid = (2..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+
+    match is_valid {
+        true => Ok(()),
+        false => Err("The Rust version must be a version such as 1.75".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aix::project::extras::{ci::CI, release::ReleaseTool};
+
+    fn prompter(input: &str) -> Prompter<&[u8], Vec<u8>> {
+        Prompter::new(input.as_bytes(), vec![])
+    }
+
+    #[test]
+    fn test_prompt_args() -> aix::Result<()> {
+        let defaults = UserConfig {
+            license: Some("MIT".into()),
+            extras: vec![ProjectExtra::Docker],
+            ..Default::default()
+        };
+        let input =
+            "1api\nmy_api\nworkspace\ny\n\nmsrv, lint\nmsrv, audit\n\ny\ncargo-release\ny\nJane Doe\n\n1.x\n1.80\n";
+
+        let args = prompt_args(&mut prompter(input), &defaults, NewProjectArgs::default())?;
+        assert_eq!(args.name, "my_api");
+        assert!(args.project_kind.workspace);
+        assert_eq!(args.setup_ci, Some(CI::GitHub));
+        assert_eq!(args.ci_options, vec![CiOption::Msrv, CiOption::Audit]);
+        assert_eq!(args.setup_docker, Some(true));
+        assert_eq!(args.setup_release, Some(ReleaseTool::CargoRelease));
+        assert_eq!(args.setup_updates, Some(true));
+        assert_eq!(args.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(args.license.as_deref(), Some("MIT"));
+        assert_eq!(args.rust_version.as_deref(), Some("1.80"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_args_skips_passed_options() -> aix::Result<()> {
+        let args = NewProjectArgs {
+            project_kind: ProjectKind {
+                standalone: true,
+                workspace: false,
+            },
+            setup_docker: Some(true),
+            author: Some("Jane Doe".into()),
+            license: Some("MIT".into()),
+            rust_version: Some("1.80".into()),
+            ..Default::default()
+        };
+
+        let args = prompt_args(
+            &mut prompter("my_api\nn\nn\nn\n"),
+            &UserConfig::default(),
+            args,
+        )?;
+        assert_eq!(args.name, "my_api");
+        assert_eq!(args.setup_ci, None);
+        assert!(args.ci_options.is_empty());
+        assert_eq!(args.setup_release, None);
+        assert_eq!(args.setup_updates, Some(false));
+
+        let result = prompt_args(&mut prompter(""), &UserConfig::default(), args);
+        assert!(matches!(result, Err(aix::Error::PromptClosed)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_variables() -> aix::Result<()> {
+        let variables = BTreeMap::from([
+            ("crate_name".to_string(), TemplateVariable::default()),
+            (
+                "description".to_string(),
+                TemplateVariable {
+                    description: Some("A short description".into()),
+                    default: Some("An API".into()),
+                },
+            ),
+            ("port".to_string(), TemplateVariable::default()),
+            ("owner".to_string(), TemplateVariable::default()),
+        ]);
+        let mut values = BTreeMap::from([("owner".to_string(), "acme".to_string())]);
+
+        let mut prompter = prompter("\n8080\n");
+        prompt_variables(&mut prompter, variables, &mut values)?;
+
+        assert_eq!(
+            values,
+            BTreeMap::from([
+                ("description".to_string(), "An API".to_string()),
+                ("owner".to_string(), "acme".to_string()),
+                ("port".to_string(), "8080".to_string()),
+            ])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use aix::config::{Config, DEFAULT_REGISTRY};
+use aix::git::GitRepository;
+use clap::{Args, Subcommand};
+use log::info;
+
+/// Runs a `registry` subcommand to manage the template registries.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used to access application configuration and resources.
+/// - `args`: An instance of `RegistryArgs` containing the subcommand to run.
+///
+/// # Returns
+///
+/// - `Ok(())` if the subcommand completes successfully.
+/// - `Err` if any error occurs, such as an unknown registry or file system errors.
+pub async fn run(app_handle: &Config, args: &RegistryArgs) -> aix::Result<()> {
+    match &args.command {
+        RegistryCommand::Add {
+            name,
+            url,
+            branch,
+            directory,
+            checksums_sha256,
+        } => {
+            let git_repository = GitRepository {
+                url: url.clone(),
+                branch: branch.clone().unwrap_or_default(),
+                directory: directory.clone(),
+                checksums_sha256: checksums_sha256.clone(),
+            };
+            app_handle.add_registry(name, git_repository).await?;
+            info!("Added template registry {name}");
+            Ok(())
+        }
+        RegistryCommand::List => list(app_handle).await,
+        RegistryCommand::Remove { name } => {
+            app_handle.remove_registry(name).await?;
+            info!("Removed template registry {name}");
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RegistryArgs {
+    #[command(subcommand)]
+    pub command: RegistryCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum RegistryCommand {
+    /// Adds a registry pulling project templates from a Git repository.
+    Add {
+        /// The name used to reference templates of the registry (e.g. company/api).
+        name: String,
+
+        /// The URL of the Git repository.
+        url: String,
+
+        /// The branch to pull templates from.
+        /// Defaults to the default branch of the repository.
+        #[arg(short, long, verbatim_doc_comment)]
+        branch: Option<String>,
+
+        /// The directory of the repository containing the templates.
+        #[arg(short = 'd', long = "dir", default_value = "templates")]
+        directory: String,
+
+        /// The SHA-256 checksum of the templates' SHA256SUMS manifest.
+        /// Rejects templates without a manifest or with a different one.
+        #[arg(long, value_name = "SHA256", verbatim_doc_comment)]
+        checksums_sha256: Option<String>,
+    },
+
+    /// Lists the template registries.
+    List,
+
+    /// Removes a registry along with its local template store.
+    Remove {
+        /// The name of the registry.
+        name: String,
+    },
+}
+
+async fn list(app_handle: &Config) -> aix::Result<()> {
+    let registries = app_handle.registries().await?;
+    let default_registry = app_handle.registry(DEFAULT_REGISTRY).await?;
+
+    let registries: Vec<(&str, &GitRepository)> =
+        std::iter::once((DEFAULT_REGISTRY, default_registry.git_repository()))
+            .chain(registries.iter().map(|(name, repo)| (name.as_str(), repo)))
+            .collect();
+
+    let width = registries
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, repo) in registries {
+        match repo.branch.as_str() {
+            "" => println!("{name:width$}  {}", repo.url),
+            branch => println!("{name:width$}  {} ({branch})", repo.url),
+        }
+    }
+
+    Ok(())
+}
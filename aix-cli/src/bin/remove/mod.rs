@@ -0,0 +1,108 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{env::current_dir, path::PathBuf};
+
+use aix::config::{Config, SyncMode};
+use aix::project::extras::{self, ExtraRemoval, ProjectExtra};
+use aix::project::Project;
+use clap::{value_parser, ArgAction, Args};
+use log::{info, warn};
+
+/// Removes an extra from an existing project based on provided arguments.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used to access application configuration and resources.
+/// - `args`: An instance of `RemoveExtraArgs` containing the extra to remove.
+///
+/// # Returns
+///
+/// - `Ok(())` if the extra was removed successfully.
+/// - `Err` if any error occurs, such as the project not including the extra.
+pub async fn run(app_handle: &Config, args: &RemoveExtraArgs) -> aix::Result<()> {
+    let project_dir = match &args.dir {
+        Some(dir) => dir.clone(),
+        None => current_dir()?,
+    };
+    // Projects generated by earlier versions of aix record no project.
+    let project = match Project::load(&project_dir).await? {
+        Some(project) => project,
+        None => Project::detect(&project_dir).await?,
+    };
+
+    let sync_mode = match (args.refresh, args.offline) {
+        (true, _) => SyncMode::Refresh,
+        (_, true) => SyncMode::Offline,
+        _ => SyncMode::Lazy,
+    };
+
+    let removal = extras::remove_extra(app_handle, &project, &args.extra, sync_mode).await?;
+    print_removal(&args.extra, &removal);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RemoveExtraArgs {
+    /// The extra to remove (docker, ci, release or updates).
+    #[arg(value_name = "EXTRA", value_parser = value_parser!(ProjectExtra))]
+    pub extra: ProjectExtra,
+
+    /// The directory of the project.
+    /// Defaults to the current working directory if not specified.
+    #[arg(
+        short = 'd',
+        long = "dir",
+        value_name = "DIRECTORY",
+        verbatim_doc_comment
+    )]
+    pub dir: Option<PathBuf>,
+
+    /// Pulls the latest project templates before removing the extra.
+    #[arg(
+        long,
+        action(ArgAction::SetTrue),
+        default_value_t = false,
+        conflicts_with = "offline"
+    )]
+    pub refresh: bool,
+
+    /// Removes the extra using previously pulled templates
+    /// without accessing the network.
+    #[arg(
+        long,
+        action(ArgAction::SetTrue),
+        default_value_t = false,
+        verbatim_doc_comment
+    )]
+    pub offline: bool,
+}
+
+fn print_removal(extra: &ProjectExtra, removal: &ExtraRemoval) {
+    let files = removal
+        .removed
+        .iter()
+        .map(|path| format!("\x1b[38;2;140;45;45mREMOVE\x1b[0m {}", path.display()))
+        .chain(
+            removal
+                .reverted
+                .iter()
+                .map(|path| format!("\x1b[38;2;46;86;111mREVERT\x1b[0m {}", path.display())),
+        )
+        .reduce(|prev: String, curr: String| format!("{prev}\n   {curr}"));
+
+    match files {
+        Some(files) => info!("Successfully removed the {extra} extra\n   {files}"),
+        None => info!("Removed the {extra} extra without changing files"),
+    }
+
+    let kept = removal
+        .kept
+        .iter()
+        .map(|path| path.display().to_string())
+        .reduce(|prev: String, curr: String| format!("{prev}\n   {curr}"));
+    if let Some(kept) = kept {
+        warn!("Left behind files modified since the project was generated\n   {kept}");
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::path::{Path, PathBuf};
+
+use aix::config::{Config, SyncMode, SyncStatus, TemplateRef, DEFAULT_REGISTRY};
+use aix::template::Template;
+use clap::{Args, Subcommand};
+use log::info;
+
+/// Runs a `templates` subcommand to inspect and manage the local template store.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used to access application configuration and resources.
+/// - `args`: An instance of `TemplatesArgs` containing the subcommand to run.
+///
+/// # Returns
+///
+/// - `Ok(())` if the subcommand completes successfully.
+/// - `Err` if any error occurs, such as a missing template or file system errors.
+pub async fn run(app_handle: &Config, args: &TemplatesArgs) -> aix::Result<()> {
+    let store = app_handle.registry(&args.registry).await?;
+
+    match &args.command {
+        TemplatesCommand::List => list(&store).await,
+        TemplatesCommand::Update => update(&store).await,
+        TemplatesCommand::Show { name } => show(&store, name).await,
+        TemplatesCommand::Path => {
+            println!("{}", store.templates_dir().display());
+            Ok(())
+        }
+        TemplatesCommand::Status => status(&store).await,
+        TemplatesCommand::Clean => {
+            store.clean_store().await?;
+            info!("Removed template store at {}", store.store_dir().display());
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TemplatesArgs {
+    #[command(subcommand)]
+    pub command: TemplatesCommand,
+
+    /// The registry whose template store to use.
+    #[arg(short, long, global = true, default_value = DEFAULT_REGISTRY)]
+    pub registry: String,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum TemplatesCommand {
+    /// Lists the available project templates and extras.
+    List,
+
+    /// Pulls the latest project templates from the template repository.
+    Update,
+
+    /// Shows the files and variables of a project template.
+    Show {
+        /// The name of the template (e.g. standalone),
+        /// optionally prefixed by its registry (e.g. company/api).
+        #[arg(verbatim_doc_comment)]
+        name: String,
+    },
+
+    /// Prints the path of the local template store.
+    Path,
+
+    /// Shows when and from which commit the project templates were last synced.
+    Status,
+
+    /// Removes the local template store.
+    Clean,
+}
+
+async fn list(app_handle: &Config) -> aix::Result<()> {
+    app_handle.ensure_store(SyncMode::Lazy).await?;
+
+    let templates = app_handle.templates().await?;
+    let width = templates.iter().map(|t| t.name().len()).max().unwrap_or(0);
+
+    println!("Templates:");
+    for template in &templates {
+        let description = template.description().unwrap_or_default();
+        println!("  {:width$}  {description}", template.name());
+    }
+
+    let extras = app_handle.extras().await?;
+    if !extras.is_empty() {
+        println!("\nExtras:");
+        for (name, variants) in extras {
+            if variants.is_empty() {
+                println!("  {name}");
+            } else {
+                println!("  {name} ({})", variants.join(", "));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn update(app_handle: &Config) -> aix::Result<()> {
+    let status = app_handle.sync_store().await?;
+
+    let commit = app_handle
+        .store_state()
+        .await?
+        .map(|state| state.commit.chars().take(7).collect::<String>())
+        .unwrap_or_default();
+
+    match status {
+        SyncStatus::Cloned => info!("Pulled project templates at {commit}"),
+        SyncStatus::Updated => info!("Updated project templates to {commit}"),
+        SyncStatus::UpToDate => info!("Project templates are up to date at {commit}"),
+    }
+    Ok(())
+}
+
+async fn status(app_handle: &Config) -> aix::Result<()> {
+    let _lock = app_handle.lock_store_shared().await?;
+    let Some(state) = app_handle.store_state().await? else {
+        println!("Project templates have not been synced yet");
+        return Ok(());
+    };
+
+    let synced_at = humantime::format_rfc3339_seconds(state.synced_at());
+    let stale = state.age() > app_handle.store_ttl();
+
+    match state.branch.as_str() {
+        "" => println!("Repository: {}", state.url),
+        branch => println!("Repository: {} ({branch})", state.url),
+    }
+    println!("Commit:     {}", state.commit);
+    println!(
+        "Synced at:  {synced_at}{}",
+        if stale { " (stale)" } else { "" }
+    );
+    Ok(())
+}
+
+async fn show(app_handle: &Config, name: &str) -> aix::Result<()> {
+    // A registry prefix takes precedence over the selected registry.
+    let (store, name) = match name.contains('/') {
+        true => {
+            let reference: TemplateRef = name.parse()?;
+            (
+                app_handle.registry(&reference.registry).await?,
+                reference.name,
+            )
+        }
+        false => (app_handle.clone(), name.to_string()),
+    };
+
+    store.ensure_store(SyncMode::Lazy).await?;
+
+    let template = store.template(&name).await?;
+    print_template(&template).await
+}
+
+async fn print_template(template: &Template) -> aix::Result<()> {
+    match template.description() {
+        Some(description) => println!("{} - {description}", template.name()),
+        None => println!("{}", template.name()),
+    }
+
+    println!("\nFiles:");
+    print_file_tree(&template.files().await?);
+
+    let variables = template.variables().await?;
+    if !variables.is_empty() {
+        let width = variables.keys().map(|name| name.len()).max().unwrap_or(0);
+
+        println!("\nVariables:");
+        for (name, variable) in variables {
+            let mut line = format!("  {name:width$}");
+            if let Some(description) = &variable.description {
+                line.push_str(&format!("  {description}"));
+            }
+            if let Some(default) = &variable.default {
+                line.push_str(&format!(" (default: {default})"));
+            }
+            println!("{}", line.trim_end());
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints a sorted list of relative file paths as an indented tree.
+fn print_file_tree(files: &[PathBuf]) {
+    let mut printed_dirs: Vec<&Path> = vec![];
+
+    for file in files {
+        let ancestors: Vec<&Path> = file
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .collect();
+
+        for (depth, dir) in ancestors.iter().rev().enumerate() {
+            if !printed_dirs.contains(dir) {
+                let name = dir.file_name().unwrap().to_string_lossy();
+                println!("  {}{name}/", "  ".repeat(depth));
+                printed_dirs.push(dir);
+            }
+        }
+
+        let name = file.file_name().unwrap().to_string_lossy();
+        println!("  {}{name}", "  ".repeat(ancestors.len()));
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{env::current_dir, path::PathBuf};
+
+use aix::config::{Config, SyncMode};
+use aix::project::{self, Project, ProjectUpdate, REJECT_FILE_EXTENSION};
+use clap::{ArgAction, Args};
+use log::{info, warn};
+
+/// Updates an existing project to the latest templates based on provided arguments.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used to access application configuration and resources.
+/// - `args`: An instance of `UpdateProjectArgs` containing the project to update.
+///
+/// # Returns
+///
+/// - `Ok(())` if the project was updated successfully, even if conflicts remain.
+/// - `Err` if any error occurs, such as the project not recording its template.
+pub async fn run(app_handle: &Config, args: &UpdateProjectArgs) -> aix::Result<()> {
+    let project_dir = match &args.dir {
+        Some(dir) => dir.clone(),
+        None => current_dir()?,
+    };
+    let Some(project) = Project::load(&project_dir).await? else {
+        return Err(aix::Error::ProjectNotRecorded { path: project_dir });
+    };
+
+    // Updating pulls the latest templates, unless working offline.
+    let sync_mode = match args.offline {
+        true => SyncMode::Offline,
+        false => SyncMode::Refresh,
+    };
+
+    let update = project::update_project(app_handle, &project, sync_mode).await?;
+    print_update(&project, &update);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct UpdateProjectArgs {
+    /// The directory of the project.
+    /// Defaults to the current working directory if not specified.
+    #[arg(
+        short = 'd',
+        long = "dir",
+        value_name = "DIRECTORY",
+        verbatim_doc_comment
+    )]
+    pub dir: Option<PathBuf>,
+
+    /// Updates the project to previously pulled templates
+    /// without accessing the network.
+    #[arg(
+        long,
+        action(ArgAction::SetTrue),
+        default_value_t = false,
+        verbatim_doc_comment
+    )]
+    pub offline: bool,
+}
+
+fn print_update(project: &Project, update: &ProjectUpdate) {
+    if update.is_empty() {
+        info!("The project {} is up to date", project.name());
+        return;
+    }
+
+    let files = [
+        ("\x1b[38;2;46;111;64mADD", &update.created),
+        ("\x1b[38;2;46;86;111mUPDATE", &update.updated),
+        ("\x1b[38;2;140;45;45mREMOVE", &update.removed),
+        ("\x1b[38;2;46;86;111mMERGE", &update.merged),
+    ]
+    .into_iter()
+    .flat_map(|(action, paths)| {
+        paths
+            .iter()
+            .map(move |path| format!("{action}\x1b[0m {}", path.display()))
+    })
+    .reduce(|prev: String, curr: String| format!("{prev}\n   {curr}"));
+
+    if let Some(files) = files {
+        info!(
+            "Successfully updated the project {}\n   {files}",
+            project.name()
+        );
+    }
+
+    let conflicts = update
+        .conflicted
+        .iter()
+        .map(|path| format!("{} (conflict markers)", path.display()))
+        .chain(update.rejected.iter().map(|path| {
+            format!(
+                "{} (see {}.{REJECT_FILE_EXTENSION})",
+                path.display(),
+                path.display()
+            )
+        }))
+        .chain(
+            update
+                .kept
+                .iter()
+                .map(|path| format!("{} (removed from the template)", path.display())),
+        )
+        .reduce(|prev: String, curr: String| format!("{prev}\n   {curr}"));
+
+    if let Some(conflicts) = conflicts {
+        warn!("Resolve the conflicts with files modified since the project was generated\n   {conflicts}");
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{collections::BTreeMap, path::Path};
+use tokio::fs;
+
+use crate::{template::archive::sha256_hex, Error, Result};
+
+/// The name of the checksum manifest located at the root of a template store.
+///
+/// The manifest uses the format of `sha256sum`, with one `<sha256>  <path>` line per
+/// file, where paths are relative to the manifest's directory.
+pub const CHECKSUMS_FILE_NAME: &str = "SHA256SUMS";
+
+/// Verifies the files in `dir` against the checksum manifest located in `dir`.
+///
+/// Every file must be listed in the manifest with a matching checksum. Returns
+/// whether the files were verified, which is `false` if there is no manifest
+/// and `required` is `false`.
+///
+/// As the manifest is read from the same source as the files, it only detects
+/// corrupted files, unless the manifest itself is pinned by `manifest_sha256`,
+/// which also requires the manifest.
+///
+/// # Errors
+/// - Returns `crate::Error::IntegrityCheckFailed` if the manifest is missing but
+///   required, does not match `manifest_sha256`, cannot be parsed, or does not
+///   match the files in `dir`.
+pub async fn verify_checksums(
+    dir: &Path,
+    required: bool,
+    manifest_sha256: Option<&str>,
+) -> Result<bool> {
+    let manifest_path = dir.join(CHECKSUMS_FILE_NAME);
+    if !fs::try_exists(&manifest_path).await? {
+        if required || manifest_sha256.is_some() {
+            return Err(integrity_err(format!(
+                "Missing checksum manifest {CHECKSUMS_FILE_NAME}"
+            )));
+        }
+        return Ok(false);
+    }
+
+    let manifest = fs::read_to_string(&manifest_path).await?;
+    if let Some(pinned) = manifest_sha256 {
+        if !sha256_hex(manifest.as_bytes()).eq_ignore_ascii_case(pinned.trim()) {
+            return Err(integrity_err(format!(
+                "Checksum manifest {CHECKSUMS_FILE_NAME} does not match the pinned SHA-256 {pinned}"
+            )));
+        }
+    }
+
+    let mut expected = parse_checksums(&manifest)?;
+
+    for path in crate::fs::list_files(dir).await? {
+        // Manifests always use `/` as separator.
+        let file = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if file == CHECKSUMS_FILE_NAME {
+            continue;
+        }
+
+        let Some(checksum) = expected.remove(&file) else {
+            return Err(integrity_err(format!("File {file} is not listed")));
+        };
+
+        let actual = sha256_hex(&fs::read(dir.join(&file)).await?);
+        if !actual.eq_ignore_ascii_case(&checksum) {
+            return Err(integrity_err(format!("Checksum mismatch for file {file}")));
+        }
+    }
+
+    if let Some(file) = expected.keys().next() {
+        return Err(integrity_err(format!("Listed file {file} is missing")));
+    }
+
+    Ok(true)
+}
+
+/// Checks whether `source` starts with one of the `trusted` source prefixes.
+///
+/// A prefix only matches up to a boundary of the source, so `https://github.com/acme`
+/// trusts `https://github.com/acme/aix.git` but not `https://github.com/acme-evil/aix.git`.
+/// Every source is trusted if `trusted` is empty.
+pub fn is_trusted_source(source: &str, trusted: &[String]) -> bool {
+    trusted.is_empty() || trusted.iter().any(|prefix| matches_prefix(source, prefix))
+}
+
+/// Checks whether `source` starts with `prefix`, followed by the end of `source`,
+/// a separator, or a `.git` suffix.
+fn matches_prefix(source: &str, prefix: &str) -> bool {
+    let Some(rest) = source.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let rest = rest.strip_prefix(".git").unwrap_or(rest);
+    rest.is_empty() || prefix.ends_with(['/', ':']) || rest.starts_with(['/', ':', '#'])
+}
+
+/// Parses a checksum manifest into a map of relative file paths to checksums.
+fn parse_checksums(content: &str) -> Result<BTreeMap<String, String>> {
+    let mut checksums = BTreeMap::new();
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let Some((checksum, file)) = line.split_once(char::is_whitespace) else {
+            return Err(integrity_err(format!("Invalid checksum line {line:?}")));
+        };
+
+        // `sha256sum` marks files read in binary mode with a leading `*`.
+        let file = file.trim_start().trim_start_matches('*');
+        let file = file.strip_prefix("./").unwrap_or(file);
+        checksums.insert(file.to_string(), checksum.to_string());
+    }
+
+    Ok(checksums)
+}
+
+fn integrity_err(message: String) -> Error {
+    Error::IntegrityCheckFailed { message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn create_store(dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir.join("standalone")).await?;
+        fs::write(dir.join("standalone/Cargo.toml"), "hello").await?;
+        fs::write(
+            dir.join(CHECKSUMS_FILE_NAME),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  ./standalone/Cargo.toml\n",
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksums() -> Result<()> {
+        let temp_dir = tempdir()?;
+        create_store(temp_dir.path()).await?;
+
+        assert!(verify_checksums(temp_dir.path(), true, None).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksums_missing_manifest() -> Result<()> {
+        let temp_dir = tempdir()?;
+
+        assert!(!verify_checksums(temp_dir.path(), false, None).await?);
+        let result = verify_checksums(temp_dir.path(), true, None).await;
+        assert!(matches!(result, Err(Error::IntegrityCheckFailed { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksums_pinned_manifest() -> Result<()> {
+        let temp_dir = tempdir()?;
+        create_store(temp_dir.path()).await?;
+        let manifest = fs::read(temp_dir.path().join(CHECKSUMS_FILE_NAME)).await?;
+        let pinned = sha256_hex(&manifest);
+
+        assert!(verify_checksums(temp_dir.path(), false, Some(&pinned)).await?);
+
+        // A file tampered with along with the manifest is detected.
+        fs::write(temp_dir.path().join("standalone/Cargo.toml"), "hello!").await?;
+        fs::write(
+            temp_dir.path().join(CHECKSUMS_FILE_NAME),
+            format!("{}  standalone/Cargo.toml\n", sha256_hex(b"hello!")),
+        )
+        .await?;
+        assert!(verify_checksums(temp_dir.path(), false, None).await?);
+        let result = verify_checksums(temp_dir.path(), false, Some(&pinned)).await;
+        assert!(matches!(result, Err(Error::IntegrityCheckFailed { .. })));
+
+        // A pinned manifest is required.
+        fs::remove_file(temp_dir.path().join(CHECKSUMS_FILE_NAME)).await?;
+        let result = verify_checksums(temp_dir.path(), false, Some(&pinned)).await;
+        assert!(matches!(result, Err(Error::IntegrityCheckFailed { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksums_rejects_modified_files() -> Result<()> {
+        let temp_dir = tempdir()?;
+        create_store(temp_dir.path()).await?;
+        fs::write(temp_dir.path().join("standalone/Cargo.toml"), "hello!").await?;
+
+        let result = verify_checksums(temp_dir.path(), false, None).await;
+        assert!(matches!(result, Err(Error::IntegrityCheckFailed { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksums_rejects_unlisted_files() -> Result<()> {
+        let temp_dir = tempdir()?;
+        create_store(temp_dir.path()).await?;
+        fs::write(temp_dir.path().join("standalone/build.rs"), "").await?;
+
+        let result = verify_checksums(temp_dir.path(), false, None).await;
+        assert!(matches!(result, Err(Error::IntegrityCheckFailed { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_trusted_source() {
+        let trusted = vec!["https://github.com/ekkolon/".to_string()];
+
+        assert!(is_trusted_source(
+            "https://github.com/ekkolon/aix.git",
+            &trusted
+        ));
+        assert!(!is_trusted_source(
+            "https://github.com/other/aix.git",
+            &trusted
+        ));
+        assert!(is_trusted_source("https://github.com/other/aix.git", &[]));
+    }
+
+    #[test]
+    fn test_is_trusted_source_lookalike_prefix() {
+        let trusted = vec!["https://github.com/acme".to_string()];
+
+        for source in [
+            "https://github.com/acme",
+            "https://github.com/acme.git",
+            "https://github.com/acme/aix.git",
+            "https://github.com/acme#main",
+        ] {
+            assert!(is_trusted_source(source, &trusted), "{source}");
+        }
+        for source in [
+            "https://github.com/acme-evil/aix.git",
+            "https://github.com/acmeevil",
+            "https://github.com/acme.github.io",
+        ] {
+            assert!(!is_trusted_source(source, &trusted), "{source}");
+        }
+    }
+}
@@ -1,26 +1,129 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use std::path::{Path, PathBuf};
+pub mod integrity;
+mod registry;
+mod state;
+mod user;
 
-use log::debug;
+pub use registry::{Registries, TemplateRef, DEFAULT_REGISTRY};
+pub use state::StoreState;
+pub use user::{
+    ConfigOrigin, UserConfig, UserConfigLayers, USER_CONFIG_ENV_PREFIX, USER_CONFIG_FILE_NAME,
+};
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
 use tokio::fs;
 
 use crate::{
-    utils::git::{self, GitRepository},
+    fs::FileLock,
+    template::{archive, archive::ArchiveSource, Template, TemplateProvenance},
+    utils::git::{self, GitBackend, GitRepository},
     Error, Result,
 };
 
 pub const RELATIVE_TEMPLATES_DIR: &str = "templates";
 pub const RELATIVE_TEMP_DIR: &str = ".tmp";
+pub const RELATIVE_CHECKOUT_DIR: &str = ".checkout";
+pub const RELATIVE_EXTRAS_DIR: &str = "extras";
+pub const RELATIVE_STATE_FILE: &str = "store.json";
+pub const RELATIVE_LOCK_FILE: &str = ".lock";
+pub const RELATIVE_ARCHIVES_DIR: &str = "archives";
+pub const RELATIVE_REGISTRIES_DIR: &str = "registries";
+pub const RELATIVE_REGISTRIES_FILE: &str = "registries.toml";
+
+/// The default time after which synced templates are considered stale.
+pub const DEFAULT_STORE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The outcome of synchronizing the local template store with its remote repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// The remote repository was cloned into a new local checkout.
+    Cloned,
+    /// The local checkout was updated to a newer remote commit.
+    Updated,
+    /// The local checkout already matches the remote commit.
+    UpToDate,
+}
+
+/// Determines whether the template store is synced before it is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Sync if the store is missing, and apply the [`RefreshPolicy`] if it is stale.
+    #[default]
+    Lazy,
+    /// Always sync, falling back to the existing store if the sync fails.
+    Refresh,
+    /// Never sync, failing if the store is missing.
+    Offline,
+}
+
+/// What to do when the synced templates are older than the configured TTL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefreshPolicy {
+    /// Warn about stale templates but use them as is.
+    #[default]
+    Warn,
+    /// Sync stale templates, falling back to the existing store if the sync fails.
+    Auto,
+}
 
+impl Display for RefreshPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warn"),
+            Self::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl FromStr for RefreshPolicy {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(Self::Warn),
+            "auto" => Ok(Self::Auto),
+            _ => Err(Error::InvalidRefreshPolicy { policy: s.into() }),
+        }
+    }
+}
+
+/// The application configuration.
+///
+/// A `Config` manages the template store of a single registry, initially the
+/// [`DEFAULT_REGISTRY`]. Use [`Config::registry`] to get the `Config` of another
+/// registry, whose store is located in its own directory.
 #[derive(Clone)]
 pub struct Config {
     name: String,
     local_data_dir: PathBuf,
+    registry: String,
+    store_dir: PathBuf,
     templates_dir: PathBuf,
     temp_dir: PathBuf,
+    checkout_dir: PathBuf,
+    state_file: PathBuf,
+    store_ttl: Duration,
+    refresh_policy: RefreshPolicy,
+    trusted_sources: Vec<String>,
+    require_checksums: bool,
+    config_file: PathBuf,
+    user_config: UserConfig,
+    user_config_layers: UserConfigLayers,
     git_repository: GitRepository,
+    default_git_repository: GitRepository,
+    git_backend: Arc<dyn GitBackend>,
 }
 
 impl Config {
@@ -29,22 +132,161 @@ impl Config {
             .ok_or(crate::Error::MissingLocalDataDir)?
             .join(app_name);
 
-        let app_templates_dir = app_local_data_dir.join(RELATIVE_TEMPLATES_DIR);
-        let app_temp_dir = app_local_data_dir.join(RELATIVE_TEMP_DIR);
+        let config = Self::with_local_data_dir(app_name, app_local_data_dir, git_repository);
+
+        match dirs::config_dir() {
+            Some(config_dir) => {
+                Ok(config.with_config_file(config_dir.join(app_name).join(USER_CONFIG_FILE_NAME)))
+            }
+            None => Ok(config),
+        }
+    }
+
+    /// Creates a new `Config` that stores its data in `local_data_dir` instead of
+    /// the user's local data directory.
+    ///
+    /// `git_repository` is the repository of the [`DEFAULT_REGISTRY`]. The user's
+    /// configuration file is located in `local_data_dir`.
+    pub fn with_local_data_dir<P: AsRef<Path>>(
+        app_name: &str,
+        local_data_dir: P,
+        git_repository: GitRepository,
+    ) -> Self {
+        let app_local_data_dir = local_data_dir.as_ref().to_path_buf();
 
-        Ok(Self {
+        Self {
             name: app_name.into(),
-            local_data_dir: app_local_data_dir,
-            templates_dir: app_templates_dir,
-            temp_dir: app_temp_dir,
-            git_repository,
-        })
+            local_data_dir: app_local_data_dir.clone(),
+            registry: String::new(),
+            store_dir: PathBuf::new(),
+            templates_dir: PathBuf::new(),
+            temp_dir: PathBuf::new(),
+            checkout_dir: PathBuf::new(),
+            state_file: PathBuf::new(),
+            store_ttl: DEFAULT_STORE_TTL,
+            refresh_policy: RefreshPolicy::default(),
+            trusted_sources: vec![],
+            require_checksums: false,
+            config_file: app_local_data_dir.join(USER_CONFIG_FILE_NAME),
+            user_config: UserConfig::default(),
+            user_config_layers: UserConfigLayers::default(),
+            git_repository: git_repository.clone(),
+            default_git_repository: git_repository,
+            git_backend: git::default_backend(),
+        }
+        .with_registry(DEFAULT_REGISTRY, None)
+    }
+
+    /// Points the template store to the directory of the registry `name`, which
+    /// pulls templates from `git_repository`, or the default repository if `None`.
+    fn with_registry(mut self, name: &str, git_repository: Option<GitRepository>) -> Self {
+        let store_dir = self.local_data_dir.join(RELATIVE_REGISTRIES_DIR).join(name);
+
+        self.registry = name.into();
+        self.templates_dir = store_dir.join(RELATIVE_TEMPLATES_DIR);
+        self.temp_dir = store_dir.join(RELATIVE_TEMP_DIR);
+        self.checkout_dir = store_dir.join(RELATIVE_CHECKOUT_DIR);
+        self.state_file = store_dir.join(RELATIVE_STATE_FILE);
+        self.store_dir = store_dir;
+        self.git_repository = git_repository.unwrap_or(self.default_git_repository.clone());
+        self
+    }
+
+    /// Replaces the [`GitBackend`] used to pull project templates.
+    pub fn with_git_backend(mut self, git_backend: Arc<dyn GitBackend>) -> Self {
+        self.git_backend = git_backend;
+        self
+    }
+
+    /// Sets the time after which synced templates are considered stale.
+    pub fn with_store_ttl(mut self, store_ttl: Duration) -> Self {
+        self.store_ttl = store_ttl;
+        self
+    }
+
+    /// Sets what to do when the synced templates are stale.
+    pub fn with_refresh_policy(mut self, refresh_policy: RefreshPolicy) -> Self {
+        self.refresh_policy = refresh_policy;
+        self
+    }
+
+    /// Restricts template sources to URLs and paths starting with one of `trusted_sources`.
+    ///
+    /// Every source is trusted if `trusted_sources` is empty, which is the default.
+    pub fn with_trusted_sources(mut self, trusted_sources: Vec<String>) -> Self {
+        self.trusted_sources = trusted_sources;
+        self
+    }
+
+    /// Sets whether synced templates must be verified against a checksum manifest.
+    ///
+    /// If not required, templates are still verified if they contain a manifest.
+    pub fn with_required_checksums(mut self, require_checksums: bool) -> Self {
+        self.require_checksums = require_checksums;
+        self
+    }
+
+    /// Sets the location of the user's configuration file.
+    pub fn with_config_file<P: AsRef<Path>>(mut self, config_file: P) -> Self {
+        self.config_file = config_file.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets the defaults for generating projects, merged from `layers`.
+    ///
+    /// Options of the template store that are set in `layers` (e.g. the trusted
+    /// sources) replace the options set before.
+    pub fn with_user_config(mut self, layers: UserConfigLayers) -> Self {
+        self.user_config = layers.resolve();
+        self.user_config_layers = layers;
+
+        if let Some(store_ttl) = self.user_config.templates_ttl {
+            self.store_ttl = store_ttl;
+        }
+        if let Some(refresh_policy) = self.user_config.templates_refresh {
+            self.refresh_policy = refresh_policy;
+        }
+        if !self.user_config.trusted_sources.is_empty() {
+            self.trusted_sources = self.user_config.trusted_sources.clone();
+        }
+        if let Some(require_checksums) = self.user_config.require_checksums {
+            self.require_checksums = require_checksums;
+        }
+        self
+    }
+
+    /// Reads templates from `templates_dir` instead of the template store,
+    /// e.g. templates exported with [`Config::export_templates`].
+    pub(crate) fn with_templates_dir<P: AsRef<Path>>(mut self, templates_dir: P) -> Self {
+        self.templates_dir = templates_dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Reads the user's configuration file and `AIX_*` environment variables, and
+    /// uses them along with the options in `flag` as the defaults for generating
+    /// projects.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidUserConfig` if the file cannot be parsed.
+    /// - Returns `crate::Error::InvalidConfigValue` if an environment variable has
+    ///   an invalid value.
+    pub async fn load_user_config(self, flag: UserConfig) -> Result<Self> {
+        let layers = UserConfigLayers {
+            file: UserConfig::load(self.config_file()).await?,
+            env: UserConfig::from_env(std::env::vars())?,
+            flag,
+        };
+        Ok(self.with_user_config(layers))
     }
 
     pub fn git_repository(&self) -> &GitRepository {
         &self.git_repository
     }
 
+    pub fn git_backend(&self) -> &dyn GitBackend {
+        self.git_backend.as_ref()
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -53,6 +295,21 @@ impl Config {
         &self.local_data_dir
     }
 
+    /// The name of the registry whose template store this `Config` manages.
+    pub fn registry_name(&self) -> &str {
+        &self.registry
+    }
+
+    /// The directory containing the template store of the registry.
+    pub fn store_dir(&self) -> &Path {
+        &self.store_dir
+    }
+
+    /// The file the user's registries are stored in.
+    pub fn registries_file(&self) -> PathBuf {
+        self.local_data_dir.join(RELATIVE_REGISTRIES_FILE)
+    }
+
     pub fn templates_dir(&self) -> &Path {
         &self.templates_dir
     }
@@ -61,44 +318,514 @@ impl Config {
         &self.temp_dir
     }
 
-    /// Pulls all project templates from the GitHub repository specified in self
-    /// and copies them to the user's local data directory of the binary.
-    pub async fn sync_store(&self) -> Result<()> {
-        if !git::is_git_installed()? {
-            return Err(Error::MissingGitInstallation);
+    /// The directory of the persistent Git checkout the templates are copied from.
+    pub fn checkout_dir(&self) -> &Path {
+        &self.checkout_dir
+    }
+
+    /// The file recording the [`StoreState`] of the last sync.
+    pub fn state_file(&self) -> &Path {
+        &self.state_file
+    }
+
+    pub fn store_ttl(&self) -> Duration {
+        self.store_ttl
+    }
+
+    pub fn refresh_policy(&self) -> RefreshPolicy {
+        self.refresh_policy
+    }
+
+    pub fn trusted_sources(&self) -> &[String] {
+        &self.trusted_sources
+    }
+
+    pub fn require_checksums(&self) -> bool {
+        self.require_checksums
+    }
+
+    /// The location of the user's configuration file.
+    pub fn config_file(&self) -> &Path {
+        &self.config_file
+    }
+
+    /// The defaults for generating projects.
+    pub fn user_config(&self) -> &UserConfig {
+        &self.user_config
+    }
+
+    /// The options of the user's configuration by origin.
+    pub fn user_config_layers(&self) -> &UserConfigLayers {
+        &self.user_config_layers
+    }
+
+    /// Ensures templates may be obtained from `url`.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::UntrustedSource` if `url` is not a trusted source.
+    fn ensure_trusted(&self, url: &str) -> Result<()> {
+        if !integrity::is_trusted_source(url, self.trusted_sources()) {
+            return Err(Error::UntrustedSource { url: url.into() });
         }
+        Ok(())
+    }
+
+    /// Reads the [`StoreState`] recorded by the last sync, if any.
+    pub async fn store_state(&self) -> Result<Option<StoreState>> {
+        StoreState::load(self.state_file()).await
+    }
+
+    /// Acquires an exclusive lock on the template store.
+    ///
+    /// The lock must be held while modifying the store, and is released when dropped.
+    pub async fn lock_store(&self) -> Result<FileLock> {
+        self.migrate_legacy_store().await?;
+        fs::create_dir_all(self.store_dir()).await?;
+        FileLock::exclusive(self.store_dir().join(RELATIVE_LOCK_FILE)).await
+    }
 
+    /// Acquires a shared lock on the template store.
+    ///
+    /// The lock prevents the store from being modified while templates are read,
+    /// and is released when dropped.
+    pub async fn lock_store_shared(&self) -> Result<FileLock> {
+        self.migrate_legacy_store().await?;
+        fs::create_dir_all(self.store_dir()).await?;
+        FileLock::shared(self.store_dir().join(RELATIVE_LOCK_FILE)).await
+    }
+
+    /// Moves the template store of the [`DEFAULT_REGISTRY`] from the data directory,
+    /// where versions without registries kept it, into the registry's directory.
+    ///
+    /// The store is only moved if the registry's directory does not exist yet, so
+    /// previously synced templates are reused instead of being synced again.
+    async fn migrate_legacy_store(&self) -> Result<()> {
         let local_data_dir = self.local_data_dir();
-        fs::create_dir_all(local_data_dir).await?;
+        if self.registry_name() != DEFAULT_REGISTRY
+            || fs::try_exists(self.store_dir()).await?
+            || !fs::try_exists(local_data_dir.join(RELATIVE_TEMPLATES_DIR)).await?
+        {
+            return Ok(());
+        }
 
-        let temp_dir = self.temp_dir();
-        fs::create_dir_all(temp_dir).await?;
+        // Versions without registries locked the store in the data directory.
+        let _lock = FileLock::exclusive(local_data_dir.join(RELATIVE_LOCK_FILE)).await?;
+        if fs::try_exists(self.store_dir()).await? {
+            return Ok(());
+        }
 
-        let git_repo = &self.git_repository();
-        debug!("Cloning remote Git repository");
-        git::clone(temp_dir, &git_repo.url, Some("."))?;
+        // Move the store to a staging directory first, so the registry's directory
+        // only exists once the whole store was moved.
+        debug!("Moving template store to {}", self.store_dir().display());
+        let registries_dir = local_data_dir.join(RELATIVE_REGISTRIES_DIR);
+        let staging_dir = registries_dir.join(format!("{DEFAULT_REGISTRY}{RELATIVE_TEMP_DIR}"));
+        crate::fs::recreate_dir(&staging_dir).await?;
+        for relative in [
+            RELATIVE_TEMPLATES_DIR,
+            RELATIVE_CHECKOUT_DIR,
+            RELATIVE_STATE_FILE,
+        ] {
+            let legacy_path = local_data_dir.join(relative);
+            if fs::try_exists(&legacy_path).await? {
+                fs::rename(legacy_path, staging_dir.join(relative)).await?;
+            }
+        }
+        fs::rename(&staging_dir, self.store_dir()).await?;
+
+        let legacy_temp_dir = local_data_dir.join(RELATIVE_TEMP_DIR);
+        if fs::try_exists(&legacy_temp_dir).await? {
+            fs::remove_dir_all(legacy_temp_dir).await?;
+        }
+        Ok(())
+    }
 
-        debug!("Sparsed checkout init");
-        git::sparse_checkout_init_cone(temp_dir)?;
+    /// Pulls all project templates from the Git repository specified in self
+    /// and copies them to the store directory of the registry.
+    ///
+    /// The repository is cloned once into a persistent checkout, which is updated
+    /// by fetching and checking out the latest remote commit on subsequent syncs.
+    /// If the remote commit did not change since the last sync, the templates
+    /// are left untouched.
+    ///
+    /// The store is locked for the duration of the sync, and fresh templates are
+    /// swapped into place by renaming, so concurrent syncs and readers never
+    /// observe a partially written template directory.
+    pub async fn sync_store(&self) -> Result<SyncStatus> {
+        let _lock = self.lock_store().await?;
+        self.sync_store_locked().await
+    }
 
-        debug!("Set path for sparsed checkout");
-        git::sparse_checkout_set_path(temp_dir, &git_repo.directory)?;
+    /// Syncs the template store. The caller must hold the exclusive store lock.
+    async fn sync_store_locked(&self) -> Result<SyncStatus> {
+        fs::create_dir_all(self.store_dir()).await?;
 
-        // Copy template files
+        let git_repo = self.git_repository();
+        self.ensure_trusted(&git_repo.url)?;
+
+        let git_backend = self.git_backend();
+        let checkout_dir = self.checkout_dir();
         let templates_dir = self.templates_dir();
-        debug!(
-            "Recreate template directories at {}",
-            templates_dir.display()
-        );
-        crate::fs::recreate_dir(templates_dir).await?;
 
-        debug!("Cleanup checkout repository before copy");
-        crate::fs::remove_files_except(temp_dir, &["templates"]).await?;
+        let status = if checkout_dir.join(".git").is_dir() {
+            debug!("Fetching remote Git repository");
+            let commit = git_backend.fetch(git_repo, checkout_dir)?;
+
+            // Compare against the recorded commit rather than the checkout, as the
+            // templates of a checked out commit may have been rejected.
+            let synced_commit = self.store_state().await?.map(|state| state.commit);
+            if synced_commit.as_deref() == Some(commit.as_str()) && templates_dir.is_dir() {
+                SyncStatus::UpToDate
+            } else {
+                debug!("Checking out commit {commit}");
+                git_backend.checkout(git_repo, checkout_dir, &commit)?;
+                SyncStatus::Updated
+            }
+        } else {
+            debug!("Cloning remote Git repository");
+            crate::fs::recreate_dir(checkout_dir).await?;
+            git_backend.sparse_clone(git_repo, checkout_dir)?;
+            SyncStatus::Cloned
+        };
+
+        let temp_dir = self.temp_dir();
+        if status != SyncStatus::UpToDate {
+            crate::fs::recreate_dir(temp_dir).await?;
+
+            debug!("Copy fresh templates to staging directory");
+            let staging_dir = temp_dir.join(RELATIVE_TEMPLATES_DIR);
+            let checkout_templates_dir = checkout_dir.join(&git_repo.directory);
+            crate::fs::copy_dir_all(checkout_templates_dir, &staging_dir).await?;
+
+            // Verify the templates before they replace the existing templates.
+            debug!("Verify checksums of fresh templates");
+            let manifest_sha256 = git_repo.checksums_sha256.as_deref();
+            if let Err(err) =
+                integrity::verify_checksums(&staging_dir, self.require_checksums(), manifest_sha256)
+                    .await
+            {
+                fs::remove_dir_all(temp_dir).await?;
+                return Err(err);
+            }
+
+            debug!("Swap templates into {}", templates_dir.display());
+            crate::fs::swap_dir(&staging_dir, templates_dir, temp_dir.join("previous")).await?;
+        }
+
+        let commit = git_backend.head_commit(checkout_dir)?;
+        StoreState::new(&git_repo.url, &git_repo.branch, &commit)
+            .save(self.state_file())
+            .await?;
+
+        // Remove the staging directory, or the temporary clone left behind by
+        // previous versions.
+        if fs::try_exists(temp_dir).await? {
+            debug!("Cleanup temporary directory {}", temp_dir.display());
+            fs::remove_dir_all(temp_dir).await?;
+        }
+
+        Ok(status)
+    }
+
+    /// Prepares the template store for use according to `mode`.
+    ///
+    /// If templates were synced before, generation is never blocked by a failing
+    /// sync: the error is logged and the existing templates are used instead.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::OfflineStoreMissing` if `mode` is `SyncMode::Offline`
+    ///   and no templates were synced before.
+    /// - Propagates errors from syncing a missing store.
+    pub async fn ensure_store(&self, mode: SyncMode) -> Result<()> {
+        // Hold the lock while checking the store, so concurrent invocations
+        // do not both find a missing store and sync it.
+        let _lock = self.lock_store().await?;
+
+        if !fs::try_exists(self.templates_dir()).await? {
+            if mode == SyncMode::Offline {
+                return Err(Error::OfflineStoreMissing);
+            }
+            self.sync_store_locked().await?;
+            return Ok(());
+        }
+
+        let refresh = match mode {
+            SyncMode::Offline => false,
+            SyncMode::Refresh => true,
+            SyncMode::Lazy => match self.stale_store_age().await? {
+                None => false,
+                Some(age) if self.refresh_policy() == RefreshPolicy::Auto => {
+                    debug!("Refreshing project templates synced {age} ago");
+                    true
+                }
+                Some(age) => {
+                    warn!(
+                        "Project templates were last synced {age} ago. \
+                         Run `{} templates update` to pull the latest templates",
+                        self.name()
+                    );
+                    false
+                }
+            },
+        };
+
+        if refresh {
+            if let Err(err) = self.sync_store_locked().await {
+                warn!("Failed to sync project templates, using existing templates: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the formatted age of the synced templates if they are older than the
+    /// configured TTL, or if their sync time is unknown.
+    async fn stale_store_age(&self) -> Result<Option<String>> {
+        let Some(state) = self.store_state().await? else {
+            return Ok(Some("an unknown time".into()));
+        };
+
+        let age = state.age();
+        if age <= self.store_ttl() {
+            return Ok(None);
+        }
+
+        // Round to whole hours for readability.
+        let age = Duration::from_secs(age.as_secs() / 3600 * 3600);
+        Ok(Some(humantime::format_duration(age).to_string()))
+    }
+
+    /// Lists all project templates in the template store, sorted by name.
+    pub async fn templates(&self) -> Result<Vec<Template>> {
+        let mut templates = vec![];
+
+        let mut entries = fs::read_dir(self.templates_dir()).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type().await?.is_dir() && name != RELATIVE_EXTRAS_DIR {
+                templates.push(Template::load(&name, entry.path()).await?);
+            }
+        }
 
-        debug!("Copy fresh templates to local directory");
-        let tmp_templates_dir = temp_dir.join(&git_repo.directory);
-        crate::fs::copy_dir_all(tmp_templates_dir, templates_dir).await?;
+        templates.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(templates)
+    }
 
+    /// Writes the templates of `commit` of the registry's Git repository to `dest`,
+    /// leaving the template store untouched.
+    ///
+    /// Like synced templates, the exported templates are verified against their
+    /// checksum file. The manifest is not compared to the pinned digest, which
+    /// belongs to the latest templates.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::Git` if `commit` was not pulled into the store before.
+    /// - Returns `crate::Error::ChecksumMismatch` if a template file does not match
+    ///   its checksum.
+    pub async fn export_templates(&self, commit: &str, dest: &Path) -> Result<()> {
+        self.git_backend()
+            .export(self.git_repository(), self.checkout_dir(), commit, dest)?;
+        integrity::verify_checksums(dest, self.require_checksums(), None).await?;
+        Ok(())
+    }
+
+    /// Gets the project template named `name` from the template store.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::TemplateNotFound` if no such template exists.
+    pub async fn template(&self, name: &str) -> Result<Template> {
+        if name == RELATIVE_EXTRAS_DIR {
+            return Err(Error::TemplateNotFound { name: name.into() });
+        }
+        let template = Template::load(name, self.templates_dir().join(name)).await?;
+
+        let Some(state) = self.store_state().await? else {
+            return Ok(template);
+        };
+
+        Ok(template.with_provenance(TemplateProvenance {
+            name: name.into(),
+            registry: Some(self.registry_name().into()),
+            source: state.url,
+            branch: (!state.branch.is_empty()).then_some(state.branch),
+            commit: Some(state.commit),
+            sha256: None,
+        }))
+    }
+
+    /// Reads the registries added by the user.
+    pub async fn registries(&self) -> Result<Registries> {
+        Registries::load(self.registries_file()).await
+    }
+
+    /// Gets the `Config` managing the template store of the registry `name`.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::RegistryNotFound` if no registry named `name` exists.
+    pub async fn registry(&self, name: &str) -> Result<Config> {
+        if name == DEFAULT_REGISTRY {
+            return Ok(self.clone().with_registry(name, None));
+        }
+
+        let registries = self.registries().await?;
+        let git_repository = registries
+            .get(name)
+            .ok_or_else(|| Error::RegistryNotFound { name: name.into() })?;
+
+        Ok(self
+            .clone()
+            .with_registry(name, Some(git_repository.clone())))
+    }
+
+    /// Adds the registry `name` pulling templates from `git_repository`.
+    ///
+    /// The templates of the registry are pulled the first time they are used.
+    pub async fn add_registry(&self, name: &str, git_repository: GitRepository) -> Result<()> {
+        let mut registries = self.registries().await?;
+        registries.add(name, git_repository)?;
+
+        fs::create_dir_all(self.local_data_dir()).await?;
+        registries.save(self.registries_file()).await
+    }
+
+    /// Removes the registry `name` along with its template store.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::RegistryNotFound` if no registry named `name` exists.
+    pub async fn remove_registry(&self, name: &str) -> Result<()> {
+        let mut registries = self.registries().await?;
+        let git_repository = registries.remove(name)?;
+        registries.save(self.registries_file()).await?;
+
+        let registry = self.clone().with_registry(name, Some(git_repository));
+
+        registry.clean_store().await?;
+        if fs::try_exists(registry.store_dir()).await? {
+            fs::remove_dir_all(registry.store_dir()).await?;
+        }
+        Ok(())
+    }
+
+    /// Loads a project template from a tar.gz or zip archive.
+    ///
+    /// The archive is read from a local file or downloaded from an HTTP(S) URL, and
+    /// extracted into a directory named after its SHA-256 digest, so extracted
+    /// archives are reused. If `sha256` is provided, the archive must match it.
+    ///
+    /// Like the template store, the extracted archives are locked while an archive
+    /// is extracted, and swapped into place by renaming once fully extracted.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::ChecksumMismatch` if the archive does not match `sha256`.
+    /// - Returns `crate::Error::UnsafeArchiveEntry` if the archive contains entries
+    ///   that would be extracted outside of its directory.
+    /// - Propagates errors from reading, downloading or extracting the archive.
+    pub async fn archive_template(
+        &self,
+        source: &ArchiveSource,
+        sha256: Option<&str>,
+    ) -> Result<Template> {
+        self.ensure_trusted(&source.to_string())?;
+
+        debug!("Fetching template archive {source}");
+        let bytes = source.fetch().await?;
+
+        if let Some(expected) = sha256 {
+            archive::verify_sha256(&bytes, expected)?;
+        }
+
+        let archives_dir = self.local_data_dir().join(RELATIVE_ARCHIVES_DIR);
+        let digest = archive::sha256_hex(&bytes);
+        let archive_dir = archives_dir.join(&digest);
+
+        // Hold the lock while checking the archive, so concurrent invocations do
+        // not both extract it into the same directory.
+        fs::create_dir_all(&archives_dir).await?;
+        let _lock = FileLock::exclusive(archives_dir.join(RELATIVE_LOCK_FILE)).await?;
+
+        if !fs::try_exists(&archive_dir).await? {
+            // Extract to a staging directory first, so a failed extraction never
+            // leaves a partial archive behind to be reused.
+            let temp_dir = archives_dir.join(RELATIVE_TEMP_DIR);
+            let staging_dir = temp_dir.join(&digest);
+            crate::fs::recreate_dir(&staging_dir).await?;
+
+            debug!("Extracting template archive to {}", archive_dir.display());
+            let extracted = archive::extract(bytes, &staging_dir).await;
+            if extracted.is_ok() {
+                crate::fs::swap_dir(&staging_dir, &archive_dir, temp_dir.join("previous")).await?;
+            }
+            fs::remove_dir_all(&temp_dir).await?;
+            extracted?;
+        }
+
+        let template_dir = archive::template_root(&archive_dir).await?;
+        let template = Template::load(&source.name(), template_dir).await?;
+
+        Ok(template.with_provenance(TemplateProvenance {
+            name: source.name(),
+            source: source.to_string(),
+            sha256: Some(digest),
+            ..Default::default()
+        }))
+    }
+
+    /// Lists the extras available in the template store, sorted by name.
+    ///
+    /// Each extra is mapped to its variants (e.g. the providers of the `ci` extra),
+    /// which is empty if the extra has no variants.
+    pub async fn extras(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let mut extras = BTreeMap::new();
+
+        let extras_dir = self.templates_dir().join(RELATIVE_EXTRAS_DIR);
+        if !fs::try_exists(&extras_dir).await? {
+            return Ok(extras);
+        }
+
+        let mut entries = fs::read_dir(extras_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+
+            // An extra has variants if it only contains visible directories.
+            let mut variants = vec![];
+            let mut has_files = false;
+            let mut extra_entries = fs::read_dir(entry.path()).await?;
+            while let Some(extra_entry) = extra_entries.next_entry().await? {
+                let name = extra_entry.file_name().to_string_lossy().to_string();
+                if extra_entry.file_type().await?.is_dir() && !name.starts_with('.') {
+                    variants.push(name);
+                } else {
+                    has_files = true;
+                }
+            }
+
+            if has_files {
+                variants.clear();
+            }
+            variants.sort();
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            extras.insert(name, variants);
+        }
+
+        Ok(extras)
+    }
+
+    /// Removes the local template store, including the cached Git checkout.
+    pub async fn clean_store(&self) -> Result<()> {
+        let _lock = self.lock_store().await?;
+
+        for dir in [self.templates_dir(), self.checkout_dir(), self.temp_dir()] {
+            if fs::try_exists(dir).await? {
+                debug!("Removing {}", dir.display());
+                fs::remove_dir_all(dir).await?;
+            }
+        }
+
+        if fs::try_exists(self.state_file()).await? {
+            fs::remove_file(self.state_file()).await?;
+        }
         Ok(())
     }
 }
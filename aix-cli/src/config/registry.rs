@@ -0,0 +1,223 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display, path::Path, str::FromStr};
+use tokio::fs;
+
+use crate::{utils::git::GitRepository, Error, Result};
+
+/// The name of the built-in registry containing the official project templates.
+pub const DEFAULT_REGISTRY: &str = "aix";
+
+/// The template registries added by the user, mapped by name.
+///
+/// The built-in [`DEFAULT_REGISTRY`] is not included.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Registries {
+    registries: BTreeMap<String, GitRepository>,
+}
+
+impl Registries {
+    /// Reads the registries from the file at `path`.
+    ///
+    /// Returns no registries if the file does not exist.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidRegistries` if the file cannot be parsed.
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !fs::try_exists(path).await? {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).await?;
+        toml::from_str(&content).map_err(|source| Error::InvalidRegistries {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })
+    }
+
+    /// Writes the registries to the file at `path`.
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = toml::to_string_pretty(self).unwrap();
+        fs::write(path, content).await?;
+        Ok(())
+    }
+
+    /// Gets the repository of the registry named `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&GitRepository> {
+        self.registries.get(name)
+    }
+
+    /// Iterates over all registries, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &GitRepository)> {
+        self.registries.iter()
+    }
+
+    /// Adds the registry `name` pulling templates from `repository`.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidRegistryName` if `name` is not a valid registry name.
+    /// - Returns `crate::Error::RegistryExists` if a registry named `name` exists.
+    pub fn add(&mut self, name: &str, repository: GitRepository) -> Result<()> {
+        validate_registry_name(name)?;
+        if name == DEFAULT_REGISTRY || self.registries.contains_key(name) {
+            return Err(Error::RegistryExists { name: name.into() });
+        }
+
+        self.registries.insert(name.into(), repository);
+        Ok(())
+    }
+
+    /// Removes the registry `name`.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::RegistryNotFound` if no registry named `name` exists.
+    pub fn remove(&mut self, name: &str) -> Result<GitRepository> {
+        self.registries
+            .remove(name)
+            .ok_or_else(|| Error::RegistryNotFound { name: name.into() })
+    }
+}
+
+/// A reference to a template of a registry, in the form of `<registry>/<template>`.
+///
+/// References without a registry (e.g. `standalone`) refer to the [`DEFAULT_REGISTRY`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateRef {
+    pub registry: String,
+    pub name: String,
+}
+
+impl TemplateRef {
+    /// Creates a reference to the template `name` of `registry`.
+    pub fn new(registry: &str, name: &str) -> Self {
+        Self {
+            registry: registry.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl Display for TemplateRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.registry, self.name)
+    }
+}
+
+impl FromStr for TemplateRef {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (registry, name) = s.split_once('/').unwrap_or((DEFAULT_REGISTRY, s));
+
+        // Both parts are used as directory names within the registries.
+        if !is_valid_name(registry) || !is_valid_name(name) {
+            return Err(Error::InvalidTemplateRef {
+                reference: s.into(),
+            });
+        }
+
+        Ok(Self::new(registry, name))
+    }
+}
+
+/// Ensures `name` can be used as a registry name, which is also used as a directory name.
+fn validate_registry_name(name: &str) -> Result<()> {
+    if !is_valid_name(name) {
+        return Err(Error::InvalidRegistryName { name: name.into() });
+    }
+    Ok(())
+}
+
+/// Checks whether `name` is a single, visible directory name.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn repository(url: &str) -> GitRepository {
+        GitRepository {
+            url: url.into(),
+            branch: "main".into(),
+            directory: "templates".into(),
+            checksums_sha256: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registries_save_and_load() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("registries.toml");
+
+        assert_eq!(Registries::load(&path).await?, Registries::default());
+
+        let mut registries = Registries::default();
+        registries.add("company", repository("git@internal:templates.git"))?;
+        registries.save(&path).await?;
+
+        let loaded = Registries::load(&path).await?;
+        assert_eq!(loaded, registries);
+        assert_eq!(
+            loaded.get("company").unwrap().url,
+            "git@internal:templates.git"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_registries_add_and_remove() -> crate::Result<()> {
+        let mut registries = Registries::default();
+        registries.add("company", repository("a"))?;
+
+        let result = registries.add("company", repository("b"));
+        assert!(matches!(result, Err(Error::RegistryExists { .. })));
+
+        let result = registries.add(DEFAULT_REGISTRY, repository("b"));
+        assert!(matches!(result, Err(Error::RegistryExists { .. })));
+
+        for name in ["", "a/b", "..", "a b"] {
+            let result = registries.add(name, repository("b"));
+            assert!(matches!(result, Err(Error::InvalidRegistryName { .. })));
+        }
+
+        assert_eq!(registries.remove("company")?.url, "a");
+        let result = registries.remove("company");
+        assert!(matches!(result, Err(Error::RegistryNotFound { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_ref_from_str() {
+        let reference: TemplateRef = "company/api".parse().unwrap();
+        assert_eq!(reference, TemplateRef::new("company", "api"));
+
+        let reference: TemplateRef = "standalone".parse().unwrap();
+        assert_eq!(reference, TemplateRef::new(DEFAULT_REGISTRY, "standalone"));
+
+        for reference in [
+            "",
+            "company/",
+            "/api",
+            "company/api/v2",
+            "company/..",
+            "../api",
+            "..",
+            "company/.hidden",
+            "company/a\\b",
+        ] {
+            assert!(reference.parse::<TemplateRef>().is_err());
+        }
+    }
+}
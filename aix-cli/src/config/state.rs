@@ -0,0 +1,115 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::fs;
+
+/// Records when and from which commit the template store was last synced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreState {
+    /// The URL of the repository the templates were pulled from.
+    pub url: String,
+    /// The branch the templates were pulled from.
+    pub branch: String,
+    /// The id of the commit the templates were copied from.
+    pub commit: String,
+    /// The time of the last sync in seconds since the Unix epoch.
+    pub synced_at: u64,
+}
+
+impl StoreState {
+    /// Creates a new `StoreState` for a sync of `commit` that happened just now.
+    pub fn new(url: &str, branch: &str, commit: &str) -> Self {
+        let synced_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Self {
+            url: url.into(),
+            branch: branch.into(),
+            commit: commit.into(),
+            synced_at,
+        }
+    }
+
+    /// Reads the state from the file at `path`.
+    ///
+    /// Returns `None` if the file does not exist or cannot be parsed, e.g. because
+    /// the store was synced by a previous version.
+    pub async fn load<P: AsRef<Path>>(path: P) -> crate::Result<Option<Self>> {
+        let path = path.as_ref();
+        if !fs::try_exists(path).await? {
+            return Ok(None);
+        }
+
+        let content = fs::read(path).await?;
+        Ok(serde_json::from_slice(&content).ok())
+    }
+
+    /// Writes the state to the file at `path`.
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let content = serde_json::to_vec_pretty(self).unwrap();
+        fs::write(path, content).await?;
+        Ok(())
+    }
+
+    /// Gets the time of the last sync.
+    pub fn synced_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.synced_at)
+    }
+
+    /// Gets the time elapsed since the last sync.
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.synced_at())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_save_and_load_state() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("store.json");
+
+        let state = StoreState::new("file:///templates.git", "main", "abc123");
+        state.save(&path).await?;
+
+        let loaded = StoreState::load(&path).await?;
+        assert_eq!(loaded, Some(state));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_or_invalid_state() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("store.json");
+
+        assert_eq!(StoreState::load(&path).await?, None);
+
+        fs::write(&path, "not json").await?;
+        assert_eq!(StoreState::load(&path).await?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_age() {
+        let mut state = StoreState::new("file:///templates.git", "main", "abc123");
+        assert!(state.age() < Duration::from_secs(60));
+
+        state.synced_at -= 3600;
+        assert!(state.age() >= Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,538 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, path::Path, time::Duration};
+use tokio::fs;
+
+use crate::{
+    config::{RefreshPolicy, DEFAULT_STORE_TTL},
+    project::{
+        extras::ci::{CiOption, CI},
+        extras::release::ReleaseTool,
+        extras::ProjectExtra,
+        ProjectType, DEFAULT_RUST_VERSION,
+    },
+    Error, Result,
+};
+
+/// The name of the user's configuration file, located in the app's config directory
+/// (e.g. `~/.config/aix/config.toml`).
+pub const USER_CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The prefix of environment variables overriding options of the user's configuration,
+/// followed by the uppercase option name (e.g. `AIX_RUST_VERSION`).
+pub const USER_CONFIG_ENV_PREFIX: &str = "AIX_";
+
+/// Defaults for generating projects, read from the user's configuration file.
+///
+/// Options passed on the command line take precedence over these defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct UserConfig {
+    /// The author of generated projects (e.g. `Jane Doe <jane@example.com>`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// The license of generated projects (e.g. `MIT OR Apache-2.0`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// The type of generated projects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ProjectType>,
+
+    /// The extras added to generated projects.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extras: Vec<ProjectExtra>,
+
+    /// The provider of the CI extra.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ci: Option<CI>,
+
+    /// The optional parts of the CI extra's pipeline (e.g. `msrv`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ci_options: Vec<CiOption>,
+
+    /// The release tool of the release extra.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release: Option<ReleaseTool>,
+
+    /// The minimum supported Rust version of generated projects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rust_version: Option<String>,
+
+    /// The template generated projects are created from, either a registry
+    /// template (e.g. `company/api`) or a template archive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// The time after which synced templates are considered stale (e.g. `12h`).
+    #[serde(with = "humantime_option", skip_serializing_if = "Option::is_none")]
+    pub templates_ttl: Option<Duration>,
+
+    /// What to do when the synced templates are stale.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub templates_refresh: Option<RefreshPolicy>,
+
+    /// The URL or path prefixes templates may be pulled from (e.g.
+    /// `https://github.com/my-org`). Every source is trusted if empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_sources: Vec<String>,
+
+    /// Whether synced templates must contain a checksum manifest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_checksums: Option<bool>,
+}
+
+impl UserConfig {
+    /// The names of all configuration options.
+    pub const KEYS: [&'static str; 13] = [
+        "author",
+        "license",
+        "kind",
+        "extras",
+        "ci",
+        "ci_options",
+        "release",
+        "rust_version",
+        "template",
+        "templates_ttl",
+        "templates_refresh",
+        "trusted_sources",
+        "require_checksums",
+    ];
+
+    /// Reads the options set by `AIX_*` environment variables from `vars`
+    /// (e.g. `std::env::vars()`).
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidConfigValue` if a variable has an invalid value.
+    pub fn from_env<I>(vars: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut config = Self::default();
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(USER_CONFIG_ENV_PREFIX) else {
+                continue;
+            };
+            let key = key.to_lowercase();
+            if Self::KEYS.contains(&key.as_str()) {
+                config.set(&key, &value)?;
+            }
+        }
+        Ok(config)
+    }
+
+    /// Gets the value of the option `key` formatted as on the command line,
+    /// or `None` if the option is not set.
+    ///
+    /// Multiple values (e.g. of `extras`) are separated by commas.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidConfigKey` if there is no option `key`.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        ensure_key(key)?;
+
+        let value = self.to_table().remove(key).map(|value| match value {
+            toml::Value::String(value) => value,
+            toml::Value::Array(values) => values
+                .iter()
+                .filter_map(|value| value.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            value => value.to_string(),
+        });
+        Ok(value)
+    }
+
+    /// Sets the option `key` to `value`, formatted as on the command line.
+    ///
+    /// Multiple values (e.g. of `extras`) are separated by commas.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidConfigKey` if there is no option `key`.
+    /// - Returns `crate::Error::InvalidConfigValue` if `value` is not valid for `key`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        ensure_key(key)?;
+
+        let toml_value = match key {
+            "extras" | "ci_options" | "trusted_sources" => toml::Value::Array(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| toml::Value::String(value.into()))
+                    .collect(),
+            ),
+            "require_checksums" => value
+                .parse()
+                .map(toml::Value::Boolean)
+                .unwrap_or_else(|_| toml::Value::String(value.into())),
+            _ => toml::Value::String(value.into()),
+        };
+
+        let mut table = self.to_table();
+        table.insert(key.into(), toml_value);
+        *self = table.try_into().map_err(|_| Error::InvalidConfigValue {
+            key: key.into(),
+            value: value.into(),
+        })?;
+        Ok(())
+    }
+
+    /// Removes the option `key`, so its default value is used.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidConfigKey` if there is no option `key`.
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        ensure_key(key)?;
+
+        let mut table = self.to_table();
+        table.remove(key);
+        *self = table.try_into().unwrap();
+        Ok(())
+    }
+
+    /// Gets the value used for the option `key` if it is not set, if any.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidConfigKey` if there is no option `key`.
+    pub fn default_value(key: &str) -> Result<Option<String>> {
+        ensure_key(key)?;
+
+        let value = match key {
+            "kind" => Some(ProjectType::default().to_string()),
+            "ci" => Some(CI::default().to_string()),
+            "release" => Some(ReleaseTool::default().to_string()),
+            "rust_version" => Some(DEFAULT_RUST_VERSION.to_string()),
+            "templates_ttl" => Some(humantime::format_duration(DEFAULT_STORE_TTL).to_string()),
+            "templates_refresh" => Some(RefreshPolicy::default().to_string()),
+            "require_checksums" => Some(false.to_string()),
+            _ => None,
+        };
+        Ok(value)
+    }
+
+    /// Sets the options set in `other`, keeping options not set in `other`.
+    pub fn merge(&mut self, other: &UserConfig) {
+        let mut table = self.to_table();
+        table.extend(other.to_table());
+        *self = table.try_into().unwrap();
+    }
+
+    /// Writes the configuration to the file at `path`, creating its directory
+    /// if necessary.
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+
+        let content = toml::to_string_pretty(self).unwrap();
+        fs::write(path, content).await?;
+        Ok(())
+    }
+
+    /// Reads the configuration from the file at `path`.
+    ///
+    /// Returns the default configuration if the file does not exist.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidUserConfig` if the file cannot be parsed,
+    ///   including if it contains unknown options.
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !fs::try_exists(path).await? {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).await?;
+        toml::from_str(&content).map_err(|source| Error::InvalidUserConfig {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })
+    }
+
+    /// Converts the configuration into a table of the options that are set.
+    fn to_table(&self) -> toml::Table {
+        toml::Table::try_from(self).expect("UserConfig always serializes to a TOML table")
+    }
+}
+
+/// Where the value of a configuration option comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    /// The option is not set, so its default value is used.
+    Default,
+    /// The option is set in the user's configuration file.
+    File,
+    /// The option is set by an `AIX_*` environment variable.
+    Env,
+    /// The option is set on the command line.
+    Flag,
+}
+
+impl Display for ConfigOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::File => write!(f, "file"),
+            Self::Env => write!(f, "env"),
+            Self::Flag => write!(f, "flag"),
+        }
+    }
+}
+
+/// The options of the user's configuration by origin.
+///
+/// Options set on the command line take precedence over environment variables,
+/// which take precedence over the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserConfigLayers {
+    pub file: UserConfig,
+    pub env: UserConfig,
+    pub flag: UserConfig,
+}
+
+impl UserConfigLayers {
+    /// Merges the layers into the configuration used to generate projects.
+    pub fn resolve(&self) -> UserConfig {
+        let mut config = self.file.clone();
+        config.merge(&self.env);
+        config.merge(&self.flag);
+        config
+    }
+
+    /// Determines where the value of the option `key` comes from.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidConfigKey` if there is no option `key`.
+    pub fn origin(&self, key: &str) -> Result<ConfigOrigin> {
+        let layers = [
+            (ConfigOrigin::Flag, &self.flag),
+            (ConfigOrigin::Env, &self.env),
+            (ConfigOrigin::File, &self.file),
+        ];
+
+        for (origin, layer) in layers {
+            if layer.get(key)?.is_some() {
+                return Ok(origin);
+            }
+        }
+        Ok(ConfigOrigin::Default)
+    }
+}
+
+/// (De)serializes an optional duration in the format of `humantime` (e.g. `12h`).
+mod humantime_option {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => serializer.collect_str(&humantime::format_duration(*duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| humantime::parse_duration(&value).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// Ensures `key` is the name of a configuration option.
+fn ensure_key(key: &str) -> Result<()> {
+    if !UserConfig::KEYS.contains(&key) {
+        return Err(Error::InvalidConfigKey { key: key.into() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_load_user_config() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join(USER_CONFIG_FILE_NAME);
+        fs::write(
+            &path,
+            r#"
+author = "Jane Doe <jane@example.com>"
+license = "MIT"
+kind = "workspace"
+extras = ["docker", "ci"]
+ci = "github"
+ci_options = ["msrv", "multi-os"]
+release = "cargo-release"
+rust_version = "1.80"
+template = "company/api"
+templates_ttl = "12h"
+templates_refresh = "auto"
+trusted_sources = ["https://github.com/my-org"]
+require_checksums = true
+"#,
+        )
+        .await?;
+
+        let config = UserConfig::load(&path).await?;
+        assert_eq!(
+            config.author.as_deref(),
+            Some("Jane Doe <jane@example.com>")
+        );
+        assert_eq!(config.license.as_deref(), Some("MIT"));
+        assert_eq!(config.kind, Some(ProjectType::Workspace));
+        assert_eq!(
+            config.extras,
+            vec![ProjectExtra::Docker, ProjectExtra::CI(CI::GitHub)]
+        );
+        assert_eq!(config.ci, Some(CI::GitHub));
+        assert_eq!(config.ci_options, vec![CiOption::Msrv, CiOption::MultiOs]);
+        assert_eq!(config.release, Some(ReleaseTool::CargoRelease));
+        assert_eq!(config.rust_version.as_deref(), Some("1.80"));
+        assert_eq!(config.template.as_deref(), Some("company/api"));
+        assert_eq!(
+            config.templates_ttl,
+            Some(Duration::from_secs(12 * 60 * 60))
+        );
+        assert_eq!(config.templates_refresh, Some(RefreshPolicy::Auto));
+        assert_eq!(config.trusted_sources, vec!["https://github.com/my-org"]);
+        assert_eq!(config.require_checksums, Some(true));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_user_config() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join(USER_CONFIG_FILE_NAME);
+
+        assert_eq!(UserConfig::load(&path).await?, UserConfig::default());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_user_config_unknown_option() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join(USER_CONFIG_FILE_NAME);
+
+        for content in [
+            "authors = \"Jane\"",
+            "kind = \"library\"",
+            "extras = [\"k8s\"]",
+        ] {
+            fs::write(&path, content).await?;
+            let result = UserConfig::load(&path).await;
+            assert!(matches!(result, Err(Error::InvalidUserConfig { .. })));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_config_get_set_unset() -> crate::Result<()> {
+        let mut config = UserConfig::default();
+        assert_eq!(config.get("author")?, None);
+
+        config.set("author", "Jane Doe")?;
+        config.set("kind", "workspace")?;
+        config.set("extras", "docker, ci")?;
+        config.set("ci_options", "audit,release")?;
+        assert_eq!(config.get("author")?.as_deref(), Some("Jane Doe"));
+        assert_eq!(config.get("kind")?.as_deref(), Some("workspace"));
+        assert_eq!(config.get("extras")?.as_deref(), Some("docker,ci"));
+        assert_eq!(config.get("ci_options")?.as_deref(), Some("audit,release"));
+        assert_eq!(config.kind, Some(ProjectType::Workspace));
+
+        config.unset("author")?;
+        assert_eq!(config.author, None);
+
+        let result = config.set("kind", "library");
+        assert!(matches!(result, Err(Error::InvalidConfigValue { .. })));
+        assert_eq!(config.kind, Some(ProjectType::Workspace));
+
+        config.set("require_checksums", "true")?;
+        assert_eq!(config.require_checksums, Some(true));
+        assert_eq!(config.get("require_checksums")?.as_deref(), Some("true"));
+        let result = config.set("require_checksums", "yes");
+        assert!(matches!(result, Err(Error::InvalidConfigValue { .. })));
+
+        let result = config.set("authors", "Jane Doe");
+        assert!(matches!(result, Err(Error::InvalidConfigKey { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_config_from_env() -> crate::Result<()> {
+        let vars = [
+            ("AIX_RUST_VERSION".to_string(), "1.80".to_string()),
+            ("AIX_TEMPLATES_TTL".to_string(), "12h".to_string()),
+            ("AIX_LOG".to_string(), "debug".to_string()),
+            ("HOME".to_string(), "/home/jane".to_string()),
+        ];
+
+        let config = UserConfig::from_env(vars)?;
+        assert_eq!(
+            config,
+            UserConfig {
+                rust_version: Some("1.80".into()),
+                templates_ttl: Some(Duration::from_secs(12 * 60 * 60)),
+                ..Default::default()
+            }
+        );
+
+        let result = UserConfig::from_env([("AIX_TEMPLATES_TTL".to_string(), "soon".to_string())]);
+        assert!(matches!(result, Err(Error::InvalidConfigValue { .. })));
+
+        let result = UserConfig::from_env([("AIX_CI".to_string(), "jenkins".to_string())]);
+        assert!(matches!(result, Err(Error::InvalidConfigValue { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_config_layers() -> crate::Result<()> {
+        let mut layers = UserConfigLayers::default();
+        layers.file.set("author", "Jane Doe")?;
+        layers.file.set("license", "MIT")?;
+        layers.env.set("license", "Apache-2.0")?;
+        layers.flag.set("rust_version", "1.80")?;
+
+        let config = layers.resolve();
+        assert_eq!(config.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(config.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(config.rust_version.as_deref(), Some("1.80"));
+
+        assert_eq!(layers.origin("author")?, ConfigOrigin::File);
+        assert_eq!(layers.origin("license")?, ConfigOrigin::Env);
+        assert_eq!(layers.origin("rust_version")?, ConfigOrigin::Flag);
+        assert_eq!(layers.origin("kind")?, ConfigOrigin::Default);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_save_user_config() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("aix").join(USER_CONFIG_FILE_NAME);
+
+        let mut config = UserConfig::default();
+        config.set("extras", "docker")?;
+        config.save(&path).await?;
+
+        assert_eq!(UserConfig::load(&path).await?, config);
+        Ok(())
+    }
+}
@@ -10,6 +10,9 @@ pub enum Error {
     #[error("Missing Git installation")]
     MissingGitInstallation,
 
+    #[error("Git operation failed: {message}")]
+    Git { message: String },
+
     #[error("Path {} is not a directory", path.display())]
     NotADirectory { path: PathBuf },
 
@@ -19,12 +22,129 @@ pub enum Error {
     #[error("Invalid project kind {kind}")]
     InvalidProjectKind { kind: String },
 
+    #[error("Invalid project extra {extra}")]
+    InvalidProjectExtra { extra: String },
+
     #[error("Invalid project CI provider {provider}")]
     InvalidProjectCiProvider { provider: String },
 
+    #[error("Invalid CI option {option}")]
+    InvalidCiOption { option: String },
+
+    #[error("Invalid release tool {tool}")]
+    InvalidReleaseTool { tool: String },
+
+    #[error("No Cargo project found at {}", path.display())]
+    NotAProject { path: PathBuf },
+
+    #[error("The project already includes the {extra} extra")]
+    ExtraExists { extra: String },
+
+    #[error("The project does not include the {extra} extra")]
+    ExtraNotFound { extra: String },
+
+    #[error("No project is recorded in the {} of {}", crate::project::PROJECT_FILE_NAME, path.display())]
+    ProjectNotRecorded { path: PathBuf },
+
+    #[error("The template {name} was not pulled from a Git repository, so it cannot be updated")]
+    TemplateNotUpdatable { name: String },
+
+    #[error(
+        "Files were modified since the project was generated: {}",
+        paths.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    ExtraConflicts { paths: Vec<PathBuf> },
+
+    #[error("Invalid extra template at {}: {message}", path.display())]
+    InvalidExtraTemplate { path: PathBuf, message: String },
+
     #[error("Failed to dermine aix data directory")]
     MissingLocalDataDir,
 
+    #[error("Invalid template refresh policy {policy}")]
+    InvalidRefreshPolicy { policy: String },
+
+    #[error("Project templates are not available offline")]
+    OfflineStoreMissing,
+
+    #[error("Template {name} not found")]
+    TemplateNotFound { name: String },
+
+    #[error("Invalid template manifest at {}: {source}", path.display())]
+    InvalidTemplateManifest {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+
+    #[error("Template registry {name} not found")]
+    RegistryNotFound { name: String },
+
+    #[error("Template registry {name} already exists")]
+    RegistryExists { name: String },
+
+    #[error("Invalid template registry name {name}")]
+    InvalidRegistryName { name: String },
+
+    #[error("Invalid template registries file at {}: {source}", path.display())]
+    InvalidRegistries {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+
+    #[error("Invalid template reference {reference}, expected <registry>/<template>")]
+    InvalidTemplateRef { reference: String },
+
+    #[error("Failed to download {url}: {message}")]
+    Download { url: String, message: String },
+
+    #[error("Unsupported archive format, expected a tar.gz or zip archive")]
+    UnsupportedArchive,
+
+    #[error("Archive entry {} would be extracted outside of the target directory", path.display())]
+    UnsafeArchiveEntry { path: PathBuf },
+
+    #[error("Template entry {} links to {} outside of the template", path.display(), target.display())]
+    UnsafeTemplateEntry { path: PathBuf, target: PathBuf },
+
+    #[error("Checksum mismatch, expected SHA-256 {expected} but got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("Invalid configuration file at {}: {source}", path.display())]
+    InvalidUserConfig {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+
+    #[error("Unknown configuration option {key}")]
+    InvalidConfigKey { key: String },
+
+    #[error("Invalid value {value} for configuration option {key}")]
+    InvalidConfigValue { key: String, value: String },
+
+    #[error("Unsupported answers file {}, expected a .json, .toml or .yaml file", path.display())]
+    UnsupportedAnswersFile { path: PathBuf },
+
+    #[error("Invalid answers file at {}: {message}", path.display())]
+    InvalidAnswers { path: PathBuf, message: String },
+
+    #[error("Input ended before all questions were answered")]
+    PromptClosed,
+
+    #[error("A project name is required when not running interactively")]
+    MissingProjectName,
+
+    #[error("Invalid project metadata at {}: {source}", path.display())]
+    InvalidProjectMetadata {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+
+    #[error("Template source {url} is not trusted")]
+    UntrustedSource { url: String },
+
+    #[error("Template integrity check failed: {message}")]
+    IntegrityCheckFailed { message: String },
+
     // --- Externals
     #[error(transparent)]
     Log(#[from] log::SetLoggerError),
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use async_recursion::async_recursion;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 
 /// Check whether a directory at specified path is empty.
@@ -51,30 +51,75 @@ pub async fn recreate_dir<P: AsRef<Path>>(path: P) -> crate::Result<()> {
 
 /// Recursively copies the contents of a source directory (`src`) to a destination directory (`dest`).
 ///
+/// Symbolic links are never followed. Links pointing to an entry within `src` are
+/// recreated as links in `dest`, while links pointing outside of `src` are rejected.
+/// Files are never written through existing symbolic links in `dest`.
+///
 /// # Arguments
 /// - `src`: The path of the source directory to copy from.
 /// - `dest`: The path of the destination directory to copy to.
 ///
 /// # Errors
+/// - Returns `crate::Error::UnsafeTemplateEntry` if `src` contains a symbolic link
+///   pointing outside of `src`, or if an entry would be written through a symbolic link.
 /// - Propagates any I/O errors encountered while reading the source directory or copying files.
-#[async_recursion]
 pub async fn copy_dir_all<S, D>(src: S, dest: D) -> crate::Result<()>
 where
     S: AsRef<Path> + Send + Sync,
     D: AsRef<Path> + Send + Sync,
 {
-    let dst_path = dest.as_ref();
-    let src_path = src.as_ref();
+    copy_dir_filtered(src, dest, &|_| true).await
+}
+
+/// Recursively copies the contents of `src` to `dest` like [`copy_dir_all`], skipping
+/// entries for which `filter` returns `false`.
+///
+/// `filter` is called with the path of each entry relative to `src`. Skipped
+/// directories are not descended into.
+pub async fn copy_dir_filtered<S, D>(src: S, dest: D, filter: &CopyFilter) -> crate::Result<()>
+where
+    S: AsRef<Path> + Send + Sync,
+    D: AsRef<Path> + Send + Sync,
+{
+    copy_dir_recursive(src.as_ref(), dest.as_ref(), Path::new(""), filter).await
+}
 
-    fs::create_dir_all(dst_path).await?;
+/// A predicate selecting the entries copied by [`copy_dir_filtered`].
+pub type CopyFilter = dyn Fn(&Path) -> bool + Send + Sync;
 
-    let mut entries = fs::read_dir(src_path).await?;
+#[async_recursion]
+async fn copy_dir_recursive(
+    src_root: &Path,
+    dest_root: &Path,
+    relative: &Path,
+    filter: &CopyFilter,
+) -> crate::Result<()> {
+    let dst_path = dest_root.join(relative);
+    // The destination root itself may be a link chosen by the user.
+    if !relative.as_os_str().is_empty() {
+        ensure_not_symlink(&dst_path).await?;
+    }
+    fs::create_dir_all(&dst_path).await?;
+
+    let mut entries = fs::read_dir(src_root.join(relative)).await?;
     while let Some(entry) = entries.next_entry().await? {
+        let entry_relative = relative.join(entry.file_name());
+        if !filter(&entry_relative) {
+            continue;
+        }
+
+        // The file type of a directory entry does not follow symbolic links.
         let file_type = entry.file_type().await?;
-        let entry_dst = dst_path.join(entry.file_name());
-        if file_type.is_dir() {
-            copy_dir_all(entry.path(), entry_dst).await?;
+        let entry_dst = dest_root.join(&entry_relative);
+
+        if file_type.is_symlink() {
+            ensure_not_symlink(&entry_dst).await?;
+            copy_symlink(src_root, &entry.path(), &entry_dst, relative).await?;
+        } else if file_type.is_dir() {
+            copy_dir_recursive(src_root, dest_root, &entry_relative, filter).await?;
         } else {
+            ensure_not_symlink(&entry_dst).await?;
+            // Copying a file also copies its permissions, including the executable bits.
             fs::copy(entry.path(), entry_dst).await?;
         }
     }
@@ -82,35 +127,254 @@ where
     Ok(())
 }
 
-/// Removes all files and directories inside the specified directory (`dir`), except for those listed in `keep_paths`.
+/// Recreates the symbolic link at `src` at `dest`, if it points to an entry within
+/// the root directory `src_root` being copied.
+///
+/// `relative_dir` is the path of the link's parent directory relative to the root.
+async fn copy_symlink(
+    src_root: &Path,
+    src: &Path,
+    dest: &Path,
+    relative_dir: &Path,
+) -> crate::Result<()> {
+    let target = fs::read_link(src).await?;
+
+    if resolve_within_root(src_root, relative_dir, &target)
+        .await
+        .is_none()
+    {
+        return Err(crate::Error::UnsafeTemplateEntry {
+            path: src.to_path_buf(),
+            target,
+        });
+    }
+
+    #[cfg(unix)]
+    fs::symlink(&target, dest).await?;
+
+    // Creating symbolic links requires special privileges on other platforms,
+    // so the link target is copied instead.
+    #[cfg(not(unix))]
+    if fs::metadata(src).await?.is_dir() {
+        copy_dir_all(src, dest).await?;
+    } else {
+        fs::copy(src, dest).await?;
+    }
+
+    Ok(())
+}
+
+/// Resolves `target` relative to `relative_dir` within the root directory `src_root`.
+///
+/// Returns `None` if the resolved path is absolute or outside of the root directory,
+/// or if it passes through another symbolic link. The file system resolves such a
+/// link before any following `..` component, which could then escape the root.
+async fn resolve_within_root(
+    src_root: &Path,
+    relative_dir: &Path,
+    target: &Path,
+) -> Option<PathBuf> {
+    let mut resolved = PathBuf::new();
+    let path = relative_dir.join(target);
+    let mut components = path.components().peekable();
+
+    while let Some(component) = components.next() {
+        match component {
+            Component::Normal(name) => {
+                resolved.push(name);
+                if components.peek().is_some() && is_symlink(&src_root.join(&resolved)).await {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(resolved)
+}
+
+/// Checks whether `path` is a symbolic link, without following it.
+async fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .await
+        .is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+/// Ensures that nothing is written through a symbolic link located at `path`.
+async fn ensure_not_symlink(path: &Path) -> crate::Result<()> {
+    match fs::symlink_metadata(path).await {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            Err(crate::Error::UnsafeTemplateEntry {
+                path: path.to_path_buf(),
+                target: fs::read_link(path).await?,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Adds the executable bits to the permissions of the file at `path`, for each
+/// class of users that may read the file.
+///
+/// This is a no-op on platforms without Unix permissions.
+///
+/// # Errors
+/// - Propagates I/O errors from reading or updating the file's permissions.
+pub async fn set_executable<P: AsRef<Path>>(path: P) -> crate::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut permissions = fs::metadata(path.as_ref()).await?.permissions();
+        let mode = permissions.mode();
+        permissions.set_mode(mode | (mode & 0o444) >> 2);
+        fs::set_permissions(path, permissions).await?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// Lists the files in `dir` recursively, as sorted paths relative to `dir`.
+///
+/// # Errors
+/// - Propagates I/O errors from reading the directories.
+pub async fn list_files<P: AsRef<Path>>(dir: P) -> crate::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(current) = dirs.pop() {
+        let mut entries = fs::read_dir(&current).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path.strip_prefix(dir).unwrap().to_path_buf());
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Reads the file at `path`, returning `None` if it does not exist.
+pub async fn read_if_exists<P: AsRef<Path>>(path: P) -> crate::Result<Option<Vec<u8>>> {
+    let path = path.as_ref();
+    match fs::try_exists(path).await? {
+        true => Ok(Some(fs::read(path).await?)),
+        false => Ok(None),
+    }
+}
+
+/// Removes the directories containing `path` that are empty, up to `root`.
+pub async fn remove_empty_dirs(root: &Path, path: &Path) -> crate::Result<()> {
+    for dir in path.ancestors().skip(1) {
+        if dir == root || !is_dir_empty(dir).await? {
+            break;
+        }
+        fs::remove_dir(dir).await?;
+    }
+    Ok(())
+}
+
+/// Replaces the directory at `dest` with the directory at `src` by renaming.
+///
+/// If `dest` exists, it is first moved aside to `backup` and removed after `src`
+/// was moved into place, so `dest` is never observed partially written.
 ///
 /// # Arguments
-/// - `dir`: The path to the directory where files/directories will be removed.
-/// - `keep_paths`: A list of file or directory names (strings) to keep.
+/// - `src`: The path of the fully written replacement directory.
+/// - `dest`: The path of the directory to replace.
+/// - `backup`: A path on the same file system as `dest` that does not exist.
 ///
 /// # Errors
-/// - Propagates any I/O errors encountered during file or directory removal.
-pub async fn remove_files_except<D>(dir: D, keep_paths: &[&str]) -> crate::Result<()>
+/// - Propagates any I/O errors encountered while renaming or removing directories.
+pub async fn swap_dir<S, D, B>(src: S, dest: D, backup: B) -> crate::Result<()>
 where
+    S: AsRef<Path>,
     D: AsRef<Path>,
+    B: AsRef<Path>,
 {
-    let mut entries = fs::read_dir(dir).await?;
-    while let Some(entry) = entries.next_entry().await? {
-        let path = entry.path();
-        let entry_name = path.file_name().unwrap().to_str().unwrap();
-
-        if !keep_paths.contains(&entry_name) {
-            if path.is_file() {
-                fs::remove_file(&path).await?;
-            } else if path.is_dir() {
-                fs::remove_dir_all(&path).await?;
-            }
+    let dest = dest.as_ref();
+    let backup = backup.as_ref();
+
+    let has_dest = fs::try_exists(dest).await?;
+    if has_dest {
+        fs::rename(dest, backup).await?;
+    }
+
+    if let Err(err) = fs::rename(src, dest).await {
+        // Restore the previous directory.
+        if has_dest {
+            fs::rename(backup, dest).await?;
         }
+        return Err(err.into());
+    }
+
+    if has_dest {
+        fs::remove_dir_all(backup).await?;
     }
 
     Ok(())
 }
 
+/// An advisory lock on a file, which is released when dropped.
+///
+/// Locks are cooperative: they only exclude other processes that acquire a lock
+/// on the same file, they do not prevent access to the locked file or to any
+/// data it guards.
+#[derive(Debug)]
+pub struct FileLock {
+    _file: std::fs::File,
+}
+
+impl FileLock {
+    /// Acquires an exclusive lock on the file at `path`, creating it if it does not exist.
+    ///
+    /// Waits until all other locks on the file are released.
+    pub async fn exclusive<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::acquire(path.as_ref().to_path_buf(), true).await
+    }
+
+    /// Acquires a shared lock on the file at `path`, creating it if it does not exist.
+    ///
+    /// Waits until an exclusive lock on the file is released.
+    pub async fn shared<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::acquire(path.as_ref().to_path_buf(), false).await
+    }
+
+    async fn acquire(path: PathBuf, exclusive: bool) -> crate::Result<Self> {
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(path)?;
+
+            if exclusive {
+                file.lock()?;
+            } else {
+                file.lock_shared()?;
+            }
+
+            Ok(Self { _file: file })
+        })
+        .await
+        .unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +382,25 @@ mod tests {
     use tokio::fs::File;
     use tokio::io::AsyncWriteExt;
 
+    #[tokio::test]
+    async fn test_list_files() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        fs::create_dir_all(temp_dir.path().join("src/bin")).await?;
+        fs::create_dir_all(temp_dir.path().join("empty")).await?;
+        fs::write(temp_dir.path().join("src/bin/main.rs"), "").await?;
+        fs::write(temp_dir.path().join("Cargo.toml"), "").await?;
+
+        let files = list_files(temp_dir.path()).await?;
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("src/bin/main.rs")
+            ]
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_recreate_dir_create_new() -> crate::Result<()> {
         let temp_dir = tempdir()?;
@@ -186,34 +469,176 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_dir_all_preserves_inner_symlinks() -> crate::Result<()> {
+        let src_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+
+        fs::create_dir_all(src_dir.path().join("scripts")).await?;
+        fs::write(src_dir.path().join("README.md"), "readme").await?;
+        fs::symlink("../README.md", src_dir.path().join("scripts/README.md")).await?;
+
+        copy_dir_all(src_dir.path(), dest_dir.path()).await?;
+
+        let link = dest_dir.path().join("scripts/README.md");
+        assert!(fs::symlink_metadata(&link).await?.is_symlink());
+        assert_eq!(fs::read_link(&link).await?, Path::new("../README.md"));
+        assert_eq!(fs::read_to_string(&link).await?, "readme");
+        Ok(())
+    }
+
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_remove_files_except() -> crate::Result<()> {
+    async fn test_copy_dir_all_rejects_escaping_symlinks() -> crate::Result<()> {
+        let src_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        fs::create_dir_all(src_dir.path().join("src")).await?;
+
+        for target in ["../../secret", "/etc/passwd"] {
+            let link = src_dir.path().join("src/link");
+            fs::symlink(target, &link).await?;
+
+            let result = copy_dir_all(src_dir.path(), dest_dir.path()).await;
+            assert!(matches!(
+                result,
+                Err(crate::Error::UnsafeTemplateEntry { .. })
+            ));
+            fs::remove_file(link).await?;
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_dir_all_rejects_symlinks_escaping_through_symlinks() -> crate::Result<()> {
+        let src_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        fs::create_dir_all(src_dir.path().join("d")).await?;
+        fs::symlink("..", src_dir.path().join("d/up")).await?;
+        fs::symlink("d/up/..", src_dir.path().join("esc")).await?;
+
+        let result = copy_dir_all(src_dir.path(), dest_dir.path()).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::UnsafeTemplateEntry { target, .. }) if target == Path::new("d/up/..")
+        ));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_dir_all_never_writes_through_symlinks() -> crate::Result<()> {
+        let src_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        let outside_dir = tempdir()?;
+
+        fs::create_dir_all(src_dir.path().join("src")).await?;
+        fs::write(src_dir.path().join("src/main.rs"), "").await?;
+        fs::symlink(outside_dir.path(), dest_dir.path().join("src")).await?;
+
+        let result = copy_dir_all(src_dir.path(), dest_dir.path()).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::UnsafeTemplateEntry { .. })
+        ));
+        assert!(!outside_dir.path().join("main.rs").exists());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_copy_dir_all_preserves_permissions() -> crate::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let src_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        let script = src_dir.path().join("dev.sh");
+        fs::write(&script, "#!/bin/sh").await?;
+        fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).await?;
+
+        copy_dir_all(src_dir.path(), dest_dir.path()).await?;
+
+        let metadata = fs::metadata(dest_dir.path().join("dev.sh")).await?;
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_set_executable() -> crate::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
         let temp_dir = tempdir()?;
-        let file1 = temp_dir.path().join("keep.txt");
-        let file2 = temp_dir.path().join("remove.txt");
+        let script = temp_dir.path().join("dev.sh");
+        fs::write(&script, "#!/bin/sh").await?;
+        fs::set_permissions(&script, std::fs::Permissions::from_mode(0o640)).await?;
 
-        // Create files
-        File::create(&file1).await?;
-        File::create(&file2).await?;
+        set_executable(&script).await?;
 
-        // Remove files except `keep.txt`
-        remove_files_except(temp_dir.path(), &["keep.txt"]).await?;
+        let metadata = fs::metadata(&script).await?;
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_swap_dir() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let src = temp_dir.path().join("src");
+        let dest = temp_dir.path().join("dest");
+        let backup = temp_dir.path().join("backup");
+
+        fs::create_dir_all(&src).await?;
+        fs::write(src.join("new.txt"), "new").await?;
+        fs::create_dir_all(&dest).await?;
+        fs::write(dest.join("old.txt"), "old").await?;
+
+        swap_dir(&src, &dest, &backup).await?;
+
+        assert!(dest.join("new.txt").is_file());
+        assert!(!dest.join("old.txt").exists());
+        assert!(!src.exists());
+        assert!(!backup.exists());
+        Ok(())
+    }
 
-        assert!(file1.exists());
-        assert!(!file2.exists());
+    #[tokio::test]
+    async fn test_swap_dir_missing_dest() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let src = temp_dir.path().join("src");
+        let dest = temp_dir.path().join("dest");
+
+        fs::create_dir_all(&src).await?;
+        swap_dir(&src, &dest, temp_dir.path().join("backup")).await?;
 
+        assert!(dest.is_dir());
+        assert!(!src.exists());
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_remove_no_files() -> crate::Result<()> {
+    async fn test_file_lock_exclusive() -> crate::Result<()> {
         let temp_dir = tempdir()?;
+        let path = temp_dir.path().join(".lock");
 
-        // Remove files except no files
-        remove_files_except(temp_dir.path(), &[]).await?;
+        let lock = FileLock::exclusive(&path).await?;
 
-        assert!(temp_dir.path().is_dir());
+        let waiting = tokio::spawn(FileLock::shared(path.clone()));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(lock);
+        waiting.await.unwrap()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_file_lock_shared() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join(".lock");
 
+        let _first = FileLock::shared(&path).await?;
+        let _second = FileLock::shared(&path).await?;
         Ok(())
     }
 }
@@ -6,7 +6,9 @@ mod error;
 mod fs;
 pub mod log;
 pub mod project;
+pub mod template;
 mod utils;
 
 pub use error::*;
-pub use utils::git;
+pub use fs::FileLock;
+pub use utils::{answers, git, prompt};
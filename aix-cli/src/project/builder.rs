@@ -1,25 +1,64 @@
-use crate::project::extras::{self, ci::CI, ProjectExtra};
-use crate::{config::Config, utils::interpolation::replace_template_vars_all};
-
-use serde_json::json;
+use crate::config::{Config, SyncMode, TemplateRef};
+use crate::project::extras::{
+    self,
+    ci::{CiOption, CI},
+    release::ReleaseTool,
+    updates::{DEPENDABOT_FILE_PATH, RENOVATE_FILE_NAME},
+    ProjectExtra,
+};
+use crate::project::{ProjectMetadata, PROJECT_FILE_NAME};
+use crate::template::Template;
+use crate::utils::interpolation::replace_template_vars_all;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use tokio::fs;
 
 /// A builder for constructing `Project` instances.
 #[derive(Clone)]
 pub struct ProjectBuilder {
     app_handle: Config,
     project: Project,
+    sync_mode: SyncMode,
+    template: Option<Template>,
+    template_ref: Option<TemplateRef>,
 }
 
 impl ProjectBuilder {
-    /// Creates a new `ProjectBuilder` with a default `Project` instance.
+    /// Creates a new `ProjectBuilder` with a `Project` instance set up according to
+    /// the defaults of the user's configuration.
     pub fn new(app_handle: Config) -> Self {
+        let defaults = app_handle.user_config();
+
+        // The CI and release extras use the configured CI provider and release tool.
+        let extras = defaults
+            .extras
+            .iter()
+            .map(|extra| match (extra, &defaults.ci, &defaults.release) {
+                (ProjectExtra::CI(_), Some(ci), _) => ProjectExtra::CI(ci.clone()),
+                (ProjectExtra::Release(_), _, Some(tool)) => ProjectExtra::Release(tool.clone()),
+                (extra, _, _) => extra.clone(),
+            })
+            .collect();
+
+        let project = Project {
+            typ: defaults.kind.unwrap_or_default(),
+            extras,
+            author: defaults.author.clone(),
+            license: defaults.license.clone(),
+            rust_version: defaults.rust_version.clone(),
+            ci_options: defaults.ci_options.clone(),
+            ..Default::default()
+        };
+
         Self {
             app_handle,
-            project: Project::default(),
+            project,
+            sync_mode: SyncMode::default(),
+            template: None,
+            template_ref: None,
         }
     }
 
@@ -41,23 +80,102 @@ impl ProjectBuilder {
         self
     }
 
-    /// Configures the project to include CI setup based on the specified boolean flag.
-    pub fn setup_ci(mut self, yes: bool) -> Self {
-        if yes && !self.project.has_extra(&ProjectExtra::CI) {
-            self.project.add_extra(ProjectExtra::CI);
-            return self;
+    /// Sets the author of the project.
+    pub fn author(mut self, author: &str) -> Self {
+        self.project.author = Some(author.into());
+        self
+    }
+
+    /// Sets the license of the project.
+    pub fn license(mut self, license: &str) -> Self {
+        self.project.license = Some(license.into());
+        self
+    }
+
+    /// Sets the minimum supported Rust version of the project.
+    pub fn rust_version(mut self, rust_version: &str) -> Self {
+        self.project.rust_version = Some(rust_version.into());
+        self
+    }
+
+    /// Sets the value of the template variable `name`.
+    ///
+    /// Template variables without a value use the default declared in the
+    /// template manifest, if any.
+    pub fn variable(mut self, name: &str, value: &str) -> Self {
+        self.project.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Configures the project to include CI setup using the CI provider `ci`,
+    /// or to not include CI setup if `None`.
+    pub fn setup_ci(mut self, ci: Option<CI>) -> Self {
+        if let Some(ci) = self.project.ci().cloned() {
+            self.project.remove_extra(&ProjectExtra::CI(ci));
+        }
+        if let Some(ci) = ci {
+            self.project.add_extra(ProjectExtra::CI(ci));
+        }
+        self
+    }
+
+    /// Configures the project to include release automation using the release tool
+    /// `tool`, or to not include release automation if `None`.
+    pub fn setup_release(mut self, tool: Option<ReleaseTool>) -> Self {
+        if let Some(tool) = self.project.release().cloned() {
+            self.project.remove_extra(&ProjectExtra::Release(tool));
         }
-        self.project.remove_extra(&ProjectExtra::CI);
+        if let Some(tool) = tool {
+            self.project.add_extra(ProjectExtra::Release(tool));
+        }
+        self
+    }
+
+    /// Sets the optional parts of the CI pipeline (e.g. an MSRV job), which are
+    /// used if the project includes CI setup.
+    pub fn ci_options(mut self, options: Vec<CiOption>) -> Self {
+        self.project.ci_options = options;
         self
     }
 
     /// Configures the project to include Docker setup based on the specified boolean flag.
     pub fn setup_docker(mut self, yes: bool) -> Self {
-        if yes && !self.project.has_extra(&ProjectExtra::Docker) {
+        if !yes {
+            self.project.remove_extra(&ProjectExtra::Docker);
+        } else if !self.project.has_extra(&ProjectExtra::Docker) {
             self.project.add_extra(ProjectExtra::Docker);
-            return self;
         }
-        self.project.remove_extra(&ProjectExtra::Docker);
+        self
+    }
+
+    /// Configures the project to include the configuration of a bot updating its
+    /// dependencies based on the specified boolean flag.
+    pub fn setup_updates(mut self, yes: bool) -> Self {
+        if !yes {
+            self.project.remove_extra(&ProjectExtra::Updates);
+        } else if !self.project.has_extra(&ProjectExtra::Updates) {
+            self.project.add_extra(ProjectExtra::Updates);
+        }
+        self
+    }
+
+    /// Sets whether the project templates are synced before the project is generated.
+    pub fn sync_mode(mut self, mode: SyncMode) -> Self {
+        self.sync_mode = mode;
+        self
+    }
+
+    /// Generates the project from `template` instead of the template store's
+    /// template for the project type.
+    pub fn template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Generates the project from the registry template referenced by `reference`
+    /// instead of the template store's template for the project type.
+    pub fn template_ref(mut self, reference: TemplateRef) -> Self {
+        self.template_ref = Some(reference);
         self
     }
 
@@ -75,33 +193,69 @@ impl ProjectBuilder {
             });
         }
 
-        let template_dir = get_template_dir(&self.app_handle, typ);
+        // Resolve the template store providing the project template, unless
+        // a template was given.
+        let template_store = match (&self.template, &self.template_ref) {
+            (Some(_), _) => None,
+            (None, Some(reference)) => Some(self.app_handle.registry(&reference.registry).await?),
+            (None, None) => Some(self.app_handle.clone()),
+        };
+
+        // Extras are always provided by the app's template store.
+        let uses_app_store = template_store
+            .as_ref()
+            .is_some_and(|store| store.registry_name() == self.app_handle.registry_name());
+        let extras_store =
+            (!uses_app_store && !self.project.extras().is_empty()).then(|| self.app_handle.clone());
+
+        // Pull project templates from repository, and prevent them from being
+        // replaced while they are copied.
+        let mut _locks = vec![];
+        for store in template_store.iter().chain(&extras_store) {
+            store.ensure_store(self.sync_mode).await?;
+            _locks.push(store.lock_store_shared().await?);
+        }
 
-        if !fs::try_exists(&template_dir).await? {
-            // Pull project templates from repository.
-            self.app_handle.sync_store().await?;
+        let template = match (&self.template, &self.template_ref, &template_store) {
+            (Some(template), _, _) => template.clone(),
+            (None, Some(reference), Some(store)) => store.template(&reference.name).await?,
+            (None, _, _) => self.app_handle.template(&typ.to_string()).await?,
+        };
+
+        // Copy the project template to target src dir.
+        template.copy_to(src_root).await?;
+
+        // Use the defaults of template variables that were not set.
+        let mut project = self.project.clone();
+        for (name, variable) in template.variables().await? {
+            if let Some(default) = variable.default {
+                project.variables.entry(name).or_insert(default);
+            }
         }
 
-        // Copy templates for this project type to target src dir.
-        crate::fs::copy_dir_all(&template_dir, src_root).await?;
+        // Record the project and where its template was obtained from.
+        let metadata = ProjectMetadata {
+            project: Some(project.clone()),
+            template: template.provenance().cloned(),
+            ..Default::default()
+        };
+        metadata.save(src_root).await?;
 
-        if *typ == ProjectType::Workspace {
+        let is_typ_template = self.template.is_none() && self.template_ref.is_none();
+        if is_typ_template && *typ == ProjectType::Workspace {
             // The workspace template uses the standalone template to scaffold
             // the initial crate member.
             let crate_path = src_root.join(name);
-            let template_dir = get_template_dir(&self.app_handle, &ProjectType::Standalone);
-            crate::fs::copy_dir_all(&template_dir, crate_path).await?;
+            let template = self
+                .app_handle
+                .template(&ProjectType::Standalone.to_string())
+                .await?;
+            template.copy_to(crate_path).await?;
         }
 
-        if self.project.has_extra(&ProjectExtra::Docker) {
-            extras::docker::setup_docker(&self.app_handle, &self.project).await?;
-        }
-
-        if self.project.has_extra(&ProjectExtra::CI) {
-            extras::ci::setup_ci(&self.app_handle, &self.project, &CI::GitHub).await?;
-        }
+        extras::setup_extras(&self.app_handle, &project).await?;
 
-        Ok(self.project)
+        Ok(project)
     }
 }
 
@@ -111,7 +265,8 @@ impl ProjectBuilder {
 ///
 /// - `Standalone`: Indicates a standalone project.
 /// - `Workspace`: Indicates a workspace project, typically containing multiple projects.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProjectType {
     /// Indicates a standalone project.
     #[default]
@@ -140,12 +295,29 @@ impl FromStr for ProjectType {
     }
 }
 
+/// The minimum supported Rust version of generated projects, unless configured otherwise.
+pub const DEFAULT_RUST_VERSION: &str = "1.75";
+
+/// The template variables whose values are provided by the project itself.
+pub const BUILTIN_VARIABLES: [&str; 4] = ["crate_name", "rust_version", "author", "license"];
+
 /// Represents a project with associated metadata and configuration.
-#[derive(Clone, Default)]
+///
+/// Projects are recorded in the metadata of generated projects, without their
+/// root directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ProjectRecord", into = "ProjectRecord")]
 pub struct Project {
     pub name: String,
     pub typ: ProjectType,
     pub src_root: PathBuf,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    pub rust_version: Option<String>,
+    /// The values of template variables, keyed by name.
+    pub variables: BTreeMap<String, String>,
+    /// The optional parts of the CI pipeline.
+    pub ci_options: Vec<CiOption>,
     extras: Vec<ProjectExtra>,
 }
 
@@ -165,6 +337,7 @@ impl Project {
             name: name.into(),
             src_root: src_root.as_ref().to_path_buf(),
             extras: extras.to_vec(),
+            ..Default::default()
         })
     }
 
@@ -183,11 +356,32 @@ impl Project {
         &self.name
     }
 
+    /// Gets the minimum supported Rust version of the project.
+    pub fn rust_version(&self) -> &str {
+        self.rust_version.as_deref().unwrap_or(DEFAULT_RUST_VERSION)
+    }
+
     /// Gets the list of extra configurations.
     pub fn extras(&self) -> &Vec<ProjectExtra> {
         &self.extras
     }
 
+    /// Gets the CI provider of the project, if the project includes CI setup.
+    pub fn ci(&self) -> Option<&CI> {
+        self.extras.iter().find_map(|extra| match extra {
+            ProjectExtra::CI(ci) => Some(ci),
+            _ => None,
+        })
+    }
+
+    /// Gets the release tool of the project, if the project includes release automation.
+    pub fn release(&self) -> Option<&ReleaseTool> {
+        self.extras.iter().find_map(|extra| match extra {
+            ProjectExtra::Release(tool) => Some(tool),
+            _ => None,
+        })
+    }
+
     /// Checks if a specific extra configuration is present.
     pub fn has_extra(&self, extra: &ProjectExtra) -> bool {
         self.extras.contains(extra)
@@ -213,31 +407,304 @@ impl Project {
     }
 
     /// Compiles the project by replacing template variables in the source code.
+    ///
+    /// The digests of the compiled files are recorded in the project's metadata,
+    /// so files modified afterwards are not replaced by later changes to the project
+    /// (e.g. when removing extras).
     pub async fn compile(&self) -> crate::Result<Vec<String>> {
-        let vars = json!({
-            "crate_name": &self.name,
-            "rust_version": "1.75"
-        });
+        let files = self.replace_template_vars().await?;
+
+        let mut metadata = ProjectMetadata::load(&self.src_root)
+            .await?
+            .unwrap_or_default();
+        for file in crate::fs::list_files(&self.src_root).await? {
+            if file != Path::new(PROJECT_FILE_NAME) {
+                metadata.record_file(&self.src_root, &file).await?;
+            }
+        }
+        metadata.save(&self.src_root).await?;
+
+        Ok(files)
+    }
+
+    /// Replaces the template variables in the source code of the project.
+    pub(crate) async fn replace_template_vars(&self) -> crate::Result<Vec<String>> {
+        // Template variables cannot override the variables of the project itself.
+        let mut vars: HashMap<String, String> = self.variables.clone().into_iter().collect();
+        vars.insert("crate_name".into(), self.name.clone());
+        vars.insert("rust_version".into(), self.rust_version().to_string());
+        if let Some(author) = &self.author {
+            vars.insert("author".into(), author.clone());
+        }
+        if let Some(license) = &self.license {
+            vars.insert("license".into(), license.clone());
+        }
 
         let files = replace_template_vars_all(&self.src_root, vec![], vars).await?;
         Ok(files)
     }
+
+    /// Reads the project recorded in the metadata of the project generated at `src_root`.
+    ///
+    /// Returns `None` if the project has no metadata file, or its metadata records
+    /// no project (e.g. projects generated by earlier versions of aix).
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidProjectMetadata` if the metadata cannot be parsed.
+    pub async fn load<P: AsRef<Path>>(src_root: P) -> crate::Result<Option<Self>> {
+        let src_root = src_root.as_ref();
+        let metadata = ProjectMetadata::load(src_root).await?;
+
+        Ok(metadata
+            .and_then(|metadata| metadata.project)
+            .map(|project| Self {
+                src_root: src_root.to_path_buf(),
+                ..project
+            }))
+    }
+
+    /// Reads the project generated at `src_root` from its `Cargo.toml` and the
+    /// files of its extras.
+    ///
+    /// The options of the CI pipeline cannot be detected, so none are set.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::NotAProject` if `src_root` has no `Cargo.toml`
+    ///   declaring a package or a workspace with members.
+    pub async fn detect<P: AsRef<Path>>(src_root: P) -> crate::Result<Self> {
+        let src_root = src_root.as_ref();
+        let not_a_project = || crate::Error::NotAProject {
+            path: src_root.to_path_buf(),
+        };
+
+        // Workspace projects contain the crate named after the project as first member.
+        let manifest = read_cargo_manifest(src_root)
+            .await?
+            .ok_or_else(not_a_project)?;
+        let member = manifest
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(|members| members.get(0))
+            .and_then(|member| member.as_str());
+        let (typ, package) = match (manifest.contains_key("package"), member) {
+            (false, Some(member)) => (
+                ProjectType::Workspace,
+                read_cargo_manifest(&src_root.join(member)).await?,
+            ),
+            _ => (ProjectType::Standalone, Some(manifest)),
+        };
+
+        let package = package
+            .as_ref()
+            .and_then(|manifest| manifest.get("package"))
+            .ok_or_else(not_a_project)?;
+        let name = package
+            .get("name")
+            .and_then(|name| name.as_str())
+            .ok_or_else(not_a_project)?;
+        let rust_version = package
+            .get("rust-version")
+            .and_then(|version| version.as_str());
+
+        let exists = |path: &str| src_root.join(path).is_file();
+        let mut extras = vec![];
+        if exists("Dockerfile") {
+            extras.push(ProjectExtra::Docker);
+        }
+        if let Some(ci) = CI::ALL.into_iter().find(|ci| exists(ci.pipeline_path())) {
+            extras.push(ProjectExtra::CI(ci));
+        }
+        let tools = [ReleaseTool::ReleasePlz, ReleaseTool::CargoRelease];
+        if let Some(tool) = tools
+            .into_iter()
+            .find(|tool| exists(tool.config_file_name()))
+        {
+            extras.push(ProjectExtra::Release(tool));
+        }
+        if exists(DEPENDABOT_FILE_PATH) || exists(RENOVATE_FILE_NAME) {
+            extras.push(ProjectExtra::Updates);
+        }
+
+        Ok(Self {
+            name: name.into(),
+            typ,
+            src_root: src_root.to_path_buf(),
+            rust_version: rust_version.map(Into::into),
+            extras,
+            ..Default::default()
+        })
+    }
 }
 
-/// Retrieves the directory path for project templates based on the project type.
-///
-/// # Arguments
-///
-/// - `app_handle`: An instance of `AppHandle` used to access application configuration.
-/// - `project_type`: The type of the project for which to get the template directory.
+/// The representation of a `Project` in project metadata.
 ///
-/// # Returns
-///
-/// Returns a `PathBuf` representing the path to the directory containing templates for
-/// the specified project type.
-fn get_template_dir(app_handle: &Config, project_type: &ProjectType) -> PathBuf {
-    app_handle
-        .templates_dir()
-        .to_path_buf()
-        .join(project_type.to_string())
+/// Like in the user's configuration, the CI provider and release tool are recorded
+/// separately from the names of the extras.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct ProjectRecord {
+    name: String,
+    kind: ProjectType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rust_version: Option<String>,
+    extras: Vec<ProjectExtra>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ci: Option<CI>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ci_options: Vec<CiOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release: Option<ReleaseTool>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    variables: BTreeMap<String, String>,
+}
+
+impl From<Project> for ProjectRecord {
+    fn from(project: Project) -> Self {
+        Self {
+            ci: project.ci().cloned(),
+            release: project.release().cloned(),
+            name: project.name,
+            kind: project.typ,
+            author: project.author,
+            license: project.license,
+            rust_version: project.rust_version,
+            extras: project.extras,
+            ci_options: project.ci_options,
+            variables: project.variables,
+        }
+    }
+}
+
+impl From<ProjectRecord> for Project {
+    fn from(record: ProjectRecord) -> Self {
+        let extras = record
+            .extras
+            .into_iter()
+            .map(|extra| match (extra, &record.ci, &record.release) {
+                (ProjectExtra::CI(_), Some(ci), _) => ProjectExtra::CI(ci.clone()),
+                (ProjectExtra::Release(_), _, Some(tool)) => ProjectExtra::Release(tool.clone()),
+                (extra, _, _) => extra,
+            })
+            .collect();
+
+        Self {
+            name: record.name,
+            typ: record.kind,
+            author: record.author,
+            license: record.license,
+            rust_version: record.rust_version,
+            variables: record.variables,
+            ci_options: record.ci_options,
+            extras,
+            ..Default::default()
+        }
+    }
+}
+
+/// Reads the `Cargo.toml` located in `dir`, if any.
+async fn read_cargo_manifest(dir: &Path) -> crate::Result<Option<toml::Table>> {
+    let path = dir.join("Cargo.toml");
+    if !tokio::fs::try_exists(&path).await? {
+        return Ok(None);
+    }
+
+    let content = tokio::fs::read_to_string(&path).await?;
+    Ok(toml::from_str(&content).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{UserConfig, UserConfigLayers};
+    use crate::utils::git::GitRepository;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builder_applies_user_config() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let user_config = UserConfig {
+            author: Some("Jane Doe".into()),
+            kind: Some(ProjectType::Workspace),
+            extras: vec![ProjectExtra::Docker, "release".parse()?],
+            release: Some(ReleaseTool::CargoRelease),
+            rust_version: Some("1.80".into()),
+            ci_options: vec![CiOption::Msrv],
+            ..Default::default()
+        };
+        let git_repo = GitRepository {
+            url: "git@github.com:ekkolon/aix.git".into(),
+            branch: "main".into(),
+            directory: "templates".into(),
+            checksums_sha256: None,
+        };
+        let app_handle = Config::with_local_data_dir("aix", temp_dir.path(), git_repo)
+            .with_user_config(UserConfigLayers {
+                file: user_config,
+                ..Default::default()
+            });
+
+        let builder = ProjectBuilder::new(app_handle);
+        assert_eq!(builder.project.typ(), &ProjectType::Workspace);
+        assert!(builder.project.has_extra(&ProjectExtra::Docker));
+        assert_eq!(builder.project.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(builder.project.rust_version(), "1.80");
+        assert_eq!(builder.project.ci_options, vec![CiOption::Msrv]);
+        assert_eq!(builder.project.release(), Some(&ReleaseTool::CargoRelease));
+
+        // Options set on the builder take precedence.
+        let builder = builder.setup_docker(true).setup_ci(Some(CI::GitHub));
+        assert!(builder.project.has_extra(&ProjectExtra::Docker));
+        assert_eq!(builder.project.ci(), Some(&CI::GitHub));
+
+        let builder = builder
+            .setup_ci(None)
+            .typ(ProjectType::Standalone)
+            .setup_docker(false)
+            .rust_version("1.81")
+            .ci_options(vec![])
+            .setup_release(None);
+        assert_eq!(builder.project.typ(), &ProjectType::Standalone);
+        assert!(!builder.project.has_extra(&ProjectExtra::Docker));
+        assert_eq!(builder.project.ci(), None);
+        assert_eq!(builder.project.rust_version(), "1.81");
+        assert!(builder.project.ci_options.is_empty());
+        assert_eq!(builder.project.release(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_serialization() -> crate::Result<()> {
+        let mut project = Project {
+            name: "my_api".into(),
+            typ: ProjectType::Workspace,
+            rust_version: Some("1.80".into()),
+            ci_options: vec![CiOption::Msrv, CiOption::Audit],
+            extras: vec![
+                ProjectExtra::Docker,
+                ProjectExtra::CI(CI::GitLab),
+                ProjectExtra::Release(ReleaseTool::CargoRelease),
+            ],
+            ..Default::default()
+        };
+        project
+            .variables
+            .insert("database".into(), "postgres".into());
+
+        let content = toml::to_string_pretty(&project).unwrap();
+        assert!(content.contains("kind = \"workspace\""));
+        assert!(content.contains("ci = \"gitlab\""));
+        assert!(content.contains("release = \"cargo-release\""));
+        assert!(content.contains("[variables]"));
+        assert!(!content.contains("author"));
+
+        let deserialized: Project = toml::from_str(&content).unwrap();
+        assert_eq!(deserialized, project);
+
+        Ok(())
+    }
 }
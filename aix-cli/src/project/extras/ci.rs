@@ -1,10 +1,29 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use log::warn;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, path::Path, str::FromStr};
+use tokio::fs;
 
-use crate::{config::Config, project::Project};
+use crate::{
+    config::Config,
+    project::{extras::ProjectExtra, Project},
+};
+
+/// The name of the GitLab CI/CD pipeline file.
+pub const GITLAB_CI_FILE_NAME: &str = ".gitlab-ci.yml";
+
+/// The name of the Woodpecker CI pipeline file.
+pub const WOODPECKER_CI_FILE_NAME: &str = ".woodpecker.yml";
+
+/// The name of the file containing the beginning of a CI provider's pipeline
+/// (e.g. its triggers), which the selected jobs are appended to.
+pub const PIPELINE_FILE_NAME: &str = "pipeline.yml";
+
+/// The name of the directory containing the jobs of a CI provider's pipeline,
+/// with one `<job>.yml` file per job.
+pub const JOBS_DIR_NAME: &str = "jobs";
 
 /// A Continuous Integration (CI) provider.
 ///
@@ -13,17 +32,26 @@ use crate::{config::Config, project::Project};
 /// # Variants
 ///
 /// - `Github`: Using GitHub Actions.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+/// - `GitLab`: Using GitLab CI/CD.
+/// - `Forgejo`: Using Forgejo Actions, which also runs on Gitea.
+/// - `Woodpecker`: Using Woodpecker CI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CI {
     #[default]
     GitHub,
+    GitLab,
+    Forgejo,
+    Woodpecker,
 }
 
 impl Display for CI {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self {
-            &CI::GitHub => write!(f, "github"),
+        match self {
+            CI::GitHub => write!(f, "github"),
+            CI::GitLab => write!(f, "gitlab"),
+            CI::Forgejo => write!(f, "forgejo"),
+            CI::Woodpecker => write!(f, "woodpecker"),
         }
     }
 }
@@ -33,13 +61,112 @@ impl FromStr for CI {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "github" => Ok(Self::GitHub),
+            "gitlab" => Ok(Self::GitLab),
+            "forgejo" => Ok(Self::Forgejo),
+            "woodpecker" => Ok(Self::Woodpecker),
             _ => Err(crate::Error::InvalidProjectCiProvider { provider: s.into() }),
         }
     }
 }
 
+impl CI {
+    /// All CI providers.
+    pub const ALL: [CI; 4] = [CI::GitHub, CI::GitLab, CI::Forgejo, CI::Woodpecker];
+
+    /// Gets the path of the pipeline file, relative to the project's root directory.
+    pub fn pipeline_path(&self) -> &'static str {
+        match self {
+            CI::GitHub => ".github/workflows/ci.yml",
+            CI::GitLab => GITLAB_CI_FILE_NAME,
+            CI::Forgejo => ".forgejo/workflows/ci.yml",
+            CI::Woodpecker => WOODPECKER_CI_FILE_NAME,
+        }
+    }
+
+    /// Gets the path of the pipeline file containing the jobs of the release extra,
+    /// relative to the project's root directory.
+    ///
+    /// Providers without separate pipelines add the jobs to the CI pipeline.
+    pub fn release_pipeline_path(&self) -> &'static str {
+        match self {
+            CI::GitHub => ".github/workflows/release.yml",
+            CI::Forgejo => ".forgejo/workflows/release.yml",
+            CI::GitLab | CI::Woodpecker => self.pipeline_path(),
+        }
+    }
+}
+
+/// An optional part of the CI pipeline.
+///
+/// # Variants
+///
+/// - `Msrv`: Checks the project with its minimum supported Rust version.
+/// - `Coverage`: Measures code coverage with cargo-llvm-cov and uploads the report.
+/// - `Audit`: Checks dependencies for advisories, banned crates and unknown sources with cargo-deny.
+/// - `MultiOs`: Runs the tests on Linux, macOS and Windows.
+/// - `Release`: Publishes a release when a version tag (e.g. `v1.0.0`) is pushed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CiOption {
+    Msrv,
+    Coverage,
+    Audit,
+    MultiOs,
+    Release,
+}
+
+impl CiOption {
+    /// All CI options.
+    pub const ALL: [CiOption; 5] = [
+        CiOption::Msrv,
+        CiOption::Coverage,
+        CiOption::Audit,
+        CiOption::MultiOs,
+        CiOption::Release,
+    ];
+}
+
+impl Display for CiOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CiOption::Msrv => write!(f, "msrv"),
+            CiOption::Coverage => write!(f, "coverage"),
+            CiOption::Audit => write!(f, "audit"),
+            CiOption::MultiOs => write!(f, "multi-os"),
+            CiOption::Release => write!(f, "release"),
+        }
+    }
+}
+
+impl FromStr for CiOption {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|option| option.to_string() == s)
+            .ok_or_else(|| crate::Error::InvalidCiOption { option: s.into() })
+    }
+}
+
+impl TryFrom<String> for CiOption {
+    type Error = crate::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CiOption> for String {
+    fn from(option: CiOption) -> Self {
+        option.to_string()
+    }
+}
+
 /// Adds CI configuration to a project based on the specified CI provider.
 ///
+/// The pipeline is composed of the provider's pipeline file followed by the jobs
+/// selected by the project's CI options. Options not supported by the provider
+/// are skipped with a warning.
+///
 /// # Arguments
 ///
 /// - `app_handle`: An instance of `AppHandle` used for accessing application configuration and resources.
@@ -51,19 +178,117 @@ impl FromStr for CI {
 /// - `Ok(())` if the setup completes successfully.
 /// - `Err` if an error occurs during setup, such as file copy failures.
 pub async fn setup_ci(app_handle: &Config, project: &Project, ci: &CI) -> crate::Result<()> {
-    let docker_templates_dir = app_handle
+    let ci_templates_dir = app_handle
         .templates_dir()
         .join("extras")
         .join("ci")
         .join(ci.to_string());
 
-    match *ci {
-        CI::GitHub => {
-            let github_ci_template_dir = docker_templates_dir.join(".github");
-            let github_ci_project_dir = project.src_root().join(".github");
-            crate::fs::copy_dir_all(github_ci_template_dir, github_ci_project_dir).await?;
-        }
+    // Template stores synced by earlier versions contain the pipeline as is.
+    let pipeline_path = ci_templates_dir.join(PIPELINE_FILE_NAME);
+    if !fs::try_exists(&pipeline_path).await? {
+        return crate::fs::copy_dir_all(ci_templates_dir, project.src_root()).await;
+    }
+
+    let pipeline = fs::read_to_string(pipeline_path).await?;
+    let jobs_dir = ci_templates_dir.join(JOBS_DIR_NAME);
+    let pipeline = compose_pipeline(pipeline, &jobs_dir, ci, pipeline_jobs(project)).await?;
+
+    let project_pipeline_path = project.src_root().join(ci.pipeline_path());
+    if let Some(dir) = project_pipeline_path.parent() {
+        fs::create_dir_all(dir).await?;
     }
+    fs::write(project_pipeline_path, pipeline).await?;
 
     Ok(())
 }
+
+/// A job of a pipeline composed from job files.
+pub(crate) struct PipelineJob {
+    /// The names of the job files providing the job, in order of preference.
+    pub names: &'static [&'static str],
+    /// What requires the job (e.g. the `msrv CI option`), which is reported if
+    /// the CI provider has no job file for it. Other jobs are skipped silently.
+    pub required_by: Option<String>,
+}
+
+impl PipelineJob {
+    pub(crate) fn new(names: &'static [&'static str]) -> Self {
+        Self {
+            names,
+            required_by: None,
+        }
+    }
+
+    pub(crate) fn required_by(mut self, required_by: String) -> Self {
+        self.required_by = Some(required_by);
+        self
+    }
+}
+
+/// Appends the files of `jobs` located in `jobs_dir` to `pipeline`, separated by
+/// blank lines.
+pub(crate) async fn compose_pipeline(
+    mut pipeline: String,
+    jobs_dir: &Path,
+    ci: &CI,
+    jobs: Vec<PipelineJob>,
+) -> crate::Result<String> {
+    let mut is_first = true;
+
+    for job in jobs {
+        let mut path = None;
+        for name in job.names {
+            let job_path = jobs_dir.join(format!("{name}.yml"));
+            if fs::try_exists(&job_path).await? {
+                path = Some(job_path);
+                break;
+            }
+            if let (Some(required_by), true) = (&job.required_by, *name == job.names[0]) {
+                warn!("The {ci} CI provider does not support the {required_by}");
+            }
+        }
+
+        if let Some(path) = path {
+            if !is_first {
+                pipeline.push('\n');
+            }
+            pipeline.push_str(&fs::read_to_string(path).await?);
+            is_first = false;
+        }
+    }
+
+    Ok(pipeline)
+}
+
+/// Lists the jobs of the project's CI pipeline in order.
+fn pipeline_jobs(project: &Project) -> Vec<PipelineJob> {
+    let has_option = |option| project.ci_options.contains(&option);
+    let option_job = |option: CiOption, names| {
+        PipelineJob::new(names).required_by(format!("{option} CI option"))
+    };
+
+    let mut jobs = vec![PipelineJob::new(&["lint"])];
+    match has_option(CiOption::MultiOs) {
+        true => jobs.push(option_job(CiOption::MultiOs, &["test-multi-os", "test"])),
+        false => jobs.push(PipelineJob::new(&["test"])),
+    }
+    for (option, names) in [
+        (CiOption::Msrv, &["msrv"]),
+        (CiOption::Coverage, &["coverage"]),
+        (CiOption::Audit, &["audit"]),
+    ] {
+        if has_option(option) {
+            jobs.push(option_job(option, names));
+        }
+    }
+    jobs.push(PipelineJob::new(&["build"]));
+    if project.has_extra(&ProjectExtra::Docker) {
+        jobs.push(PipelineJob::new(&["docker"]));
+    }
+    // The release extra publishes releases with its own jobs.
+    if has_option(CiOption::Release) && project.release().is_none() {
+        jobs.push(option_job(CiOption::Release, &["release"]));
+    }
+    jobs
+}
@@ -1,12 +1,319 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    mem::discriminant,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use tokio::fs;
+
 pub mod ci;
 pub mod docker;
+pub mod release;
+pub mod updates;
+
+use ci::CI;
+use release::ReleaseTool;
+
+use crate::{
+    config::{Config, SyncMode},
+    project::{Project, ProjectMetadata},
+};
 
 /// Available types of extras available to setup when generating projects.
-#[derive(Clone, PartialEq, Eq)]
+///
+/// Extras are referenced by name (e.g. `docker` or `ci`). The CI and release
+/// extras refer to the default CI provider and release tool when referenced by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum ProjectExtra {
     Docker,
-    CI,
+    CI(CI),
+    Release(ReleaseTool),
+    Updates,
+}
+
+impl Display for ProjectExtra {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Docker => write!(f, "docker"),
+            Self::CI(_) => write!(f, "ci"),
+            Self::Release(_) => write!(f, "release"),
+            Self::Updates => write!(f, "updates"),
+        }
+    }
+}
+
+impl FromStr for ProjectExtra {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "docker" => Ok(Self::Docker),
+            "ci" => Ok(Self::CI(CI::default())),
+            "release" => Ok(Self::Release(ReleaseTool::default())),
+            "updates" => Ok(Self::Updates),
+            _ => Err(crate::Error::InvalidProjectExtra { extra: s.into() }),
+        }
+    }
+}
+
+impl TryFrom<String> for ProjectExtra {
+    type Error = crate::Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ProjectExtra> for String {
+    fn from(extra: ProjectExtra) -> Self {
+        extra.to_string()
+    }
+}
+
+/// Sets up the extras of `project` in its root directory.
+///
+/// # Errors
+/// - Propagates errors from setting up each extra, such as file copy failures.
+pub async fn setup_extras(app_handle: &Config, project: &Project) -> crate::Result<()> {
+    if project.has_extra(&ProjectExtra::Docker) {
+        docker::setup_docker(app_handle, project).await?;
+    }
+
+    if let Some(ci) = project.ci() {
+        ci::setup_ci(app_handle, project, ci).await?;
+    }
+
+    // Release jobs may be added to the CI pipeline, so CI is set up first.
+    if let Some(tool) = project.release() {
+        release::setup_release(app_handle, project, tool).await?;
+    }
+
+    if project.has_extra(&ProjectExtra::Updates) {
+        updates::setup_updates(app_handle, project).await?;
+    }
+
+    Ok(())
+}
+
+/// The changes made to the files of a project by adding an extra.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtraChanges {
+    /// The files that were created.
+    pub created: Vec<PathBuf>,
+    /// The files of other extras that were updated to work with the extra
+    /// (e.g. a CI pipeline building the Docker image).
+    pub updated: Vec<PathBuf>,
+    /// The files modified since the project was generated, which were overwritten.
+    pub overwritten: Vec<PathBuf>,
+}
+
+/// Adds `extra` to the existing `project`, whose files are updated as if the
+/// project was generated with the extra.
+///
+/// Files are compared to the digests recorded in the project's metadata, or else
+/// to the files of the project's extras as generated without `extra`. Files that
+/// differ were modified since the project was generated, and are only overwritten
+/// if `force` is `true`.
+///
+/// # Errors
+/// - Returns `crate::Error::ExtraExists` if the project already includes an extra
+///   of the same kind (e.g. CI using another provider).
+/// - Returns `crate::Error::ExtraConflicts` if files would be overwritten that were
+///   modified since the project was generated, and `force` is `false`. No files
+///   are changed in that case.
+pub async fn add_extra(
+    app_handle: &Config,
+    project: &Project,
+    extra: ProjectExtra,
+    sync_mode: SyncMode,
+    force: bool,
+) -> crate::Result<ExtraChanges> {
+    if find_extra(project, &extra).is_some() {
+        return Err(crate::Error::ExtraExists {
+            extra: extra.to_string(),
+        });
+    }
+
+    let mut new_project = project.clone();
+    new_project.add_extra(extra);
+
+    app_handle.ensure_store(sync_mode).await?;
+    let _lock = app_handle.lock_store_shared().await?;
+
+    let old_dir = tempfile::tempdir()?;
+    let new_dir = tempfile::tempdir()?;
+    render_extras(app_handle, project, old_dir.path()).await?;
+    render_extras(app_handle, &new_project, new_dir.path()).await?;
+
+    let mut metadata = ProjectMetadata::load(project.src_root())
+        .await?
+        .unwrap_or_default();
+    let mut changes = ExtraChanges::default();
+    let mut conflicts = vec![];
+
+    for file in crate::fs::list_files(new_dir.path()).await? {
+        let content = fs::read(new_dir.path().join(&file)).await?;
+        let current = crate::fs::read_if_exists(&project.src_root().join(&file)).await?;
+        let generated = crate::fs::read_if_exists(&old_dir.path().join(&file)).await?;
+
+        match current {
+            Some(current) if current == content => {}
+            Some(current) if is_modified(&metadata, &file, &current, generated.as_deref()) => {
+                conflicts.push(file)
+            }
+            Some(_) => changes.updated.push(file),
+            // Files deleted by the user count as modified.
+            None if generated.is_some() || metadata.files.contains_key(&file) => {
+                conflicts.push(file)
+            }
+            None => changes.created.push(file),
+        }
+    }
+
+    if !conflicts.is_empty() && !force {
+        return Err(crate::Error::ExtraConflicts { paths: conflicts });
+    }
+    changes.overwritten = conflicts;
+
+    for file in changes
+        .created
+        .iter()
+        .chain(&changes.updated)
+        .chain(&changes.overwritten)
+    {
+        let dest = project.src_root().join(file);
+        if let Some(dir) = dest.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        fs::copy(new_dir.path().join(file), dest).await?;
+        metadata.record_file(project.src_root(), file).await?;
+    }
+    metadata.project = Some(new_project);
+    metadata.save(project.src_root()).await?;
+
+    Ok(changes)
+}
+
+/// The changes made to the files of a project by removing an extra.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtraRemoval {
+    /// The files of the extra that were deleted.
+    pub removed: Vec<PathBuf>,
+    /// The files of other extras that were reverted to work without the extra
+    /// (e.g. a CI pipeline no longer building the Docker image).
+    pub reverted: Vec<PathBuf>,
+    /// The files modified since the project was generated, which were left behind.
+    pub kept: Vec<PathBuf>,
+}
+
+/// Removes the extra of the same kind as `extra` from the existing `project`,
+/// whose files are reverted as if the project was generated without the extra.
+///
+/// Files are compared to the digests recorded in the project's metadata, or else
+/// to the files of the project's extras as generated with the extra. Only files
+/// that were not modified since the project was generated are deleted or reverted.
+///
+/// # Errors
+/// - Returns `crate::Error::ExtraNotFound` if the project includes no extra of
+///   the same kind.
+pub async fn remove_extra(
+    app_handle: &Config,
+    project: &Project,
+    extra: &ProjectExtra,
+    sync_mode: SyncMode,
+) -> crate::Result<ExtraRemoval> {
+    let Some(extra) = find_extra(project, extra) else {
+        return Err(crate::Error::ExtraNotFound {
+            extra: extra.to_string(),
+        });
+    };
+
+    let mut new_project = project.clone();
+    new_project.remove_extra(extra);
+
+    app_handle.ensure_store(sync_mode).await?;
+    let _lock = app_handle.lock_store_shared().await?;
+
+    let old_dir = tempfile::tempdir()?;
+    let new_dir = tempfile::tempdir()?;
+    render_extras(app_handle, project, old_dir.path()).await?;
+    render_extras(app_handle, &new_project, new_dir.path()).await?;
+
+    let mut metadata = ProjectMetadata::load(project.src_root())
+        .await?
+        .unwrap_or_default();
+    let mut removal = ExtraRemoval::default();
+
+    for file in crate::fs::list_files(old_dir.path()).await? {
+        let generated = fs::read(old_dir.path().join(&file)).await?;
+        let content = crate::fs::read_if_exists(&new_dir.path().join(&file)).await?;
+        let path = project.src_root().join(&file);
+
+        let Some(current) = crate::fs::read_if_exists(&path).await? else {
+            continue;
+        };
+        if content.as_ref() == Some(&generated) {
+            continue;
+        }
+        if is_modified(&metadata, &file, &current, Some(&generated)) {
+            removal.kept.push(file);
+            continue;
+        }
+
+        match content {
+            Some(content) => {
+                fs::write(&path, content).await?;
+                metadata.record_file(project.src_root(), &file).await?;
+                removal.reverted.push(file);
+            }
+            None => {
+                fs::remove_file(&path).await?;
+                crate::fs::remove_empty_dirs(project.src_root(), &path).await?;
+                metadata.files.remove(&file);
+                removal.removed.push(file);
+            }
+        }
+    }
+    metadata.project = Some(new_project);
+    metadata.save(project.src_root()).await?;
+
+    Ok(removal)
+}
+
+/// Finds the extra of `project` of the same kind as `extra` (e.g. CI using any provider).
+fn find_extra<'a>(project: &'a Project, extra: &ProjectExtra) -> Option<&'a ProjectExtra> {
+    project
+        .extras()
+        .iter()
+        .find(|e| discriminant(*e) == discriminant(extra))
+}
+
+/// Checks whether `current`, the content of `file` in the project, was modified
+/// since the project was generated.
+///
+/// Files without a recorded digest are compared to their `generated` content.
+fn is_modified(
+    metadata: &ProjectMetadata,
+    file: &Path,
+    current: &[u8],
+    generated: Option<&[u8]>,
+) -> bool {
+    match metadata.matches(file, current) {
+        Some(matches) => !matches,
+        None => generated != Some(current),
+    }
+}
+
+/// Sets up the extras of `project` in `dir` instead of its root directory,
+/// with the template variables replaced.
+async fn render_extras(app_handle: &Config, project: &Project, dir: &Path) -> crate::Result<()> {
+    let mut project = project.clone();
+    project.src_root = dir.to_path_buf();
+
+    setup_extras(app_handle, &project).await?;
+    project.replace_template_vars().await?;
+    Ok(())
 }
@@ -0,0 +1,142 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+use tokio::fs;
+
+use crate::{
+    config::Config,
+    project::{
+        extras::{
+            ci::{compose_pipeline, PipelineJob, JOBS_DIR_NAME, PIPELINE_FILE_NAME},
+            ProjectExtra,
+        },
+        Project,
+    },
+};
+
+/// The name of the directory of a CI provider's templates containing the jobs
+/// of the release extra.
+pub const RELEASE_JOBS_DIR_NAME: &str = "release";
+
+/// A tool updating the version and changelog of a project when releasing it.
+///
+/// # Variants
+///
+/// - `ReleasePlz`: Using release-plz, which opens release pull requests from CI.
+/// - `CargoRelease`: Using cargo-release, which is run locally.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReleaseTool {
+    #[default]
+    ReleasePlz,
+    CargoRelease,
+}
+
+impl ReleaseTool {
+    /// Gets the name of the release tool's configuration file.
+    pub fn config_file_name(&self) -> &'static str {
+        match self {
+            ReleaseTool::ReleasePlz => "release-plz.toml",
+            ReleaseTool::CargoRelease => "release.toml",
+        }
+    }
+}
+
+impl Display for ReleaseTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseTool::ReleasePlz => write!(f, "release-plz"),
+            ReleaseTool::CargoRelease => write!(f, "cargo-release"),
+        }
+    }
+}
+
+impl FromStr for ReleaseTool {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "release-plz" => Ok(Self::ReleasePlz),
+            "cargo-release" => Ok(Self::CargoRelease),
+            _ => Err(crate::Error::InvalidReleaseTool { tool: s.into() }),
+        }
+    }
+}
+
+/// Adds release automation to a project based on the specified release tool.
+///
+/// Adds the configuration of the release tool and a `CHANGELOG.md`. If the project
+/// includes CI setup, also adds jobs to the CI provider's pipelines that attach
+/// release binaries to version tags, and publish the Docker image if the project
+/// includes Docker setup.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used for accessing application configuration and resources.
+/// - `project`: The `Project` to which release automation will be added.
+/// - `tool`: The release tool to configure (e.g., `ReleaseTool::ReleasePlz`).
+///
+/// # Returns
+///
+/// - `Ok(())` if the setup completes successfully.
+/// - `Err` if an error occurs during setup, such as file copy failures.
+pub async fn setup_release(
+    app_handle: &Config,
+    project: &Project,
+    tool: &ReleaseTool,
+) -> crate::Result<()> {
+    let extras_dir = app_handle.templates_dir().join("extras");
+
+    let release_templates_dir = extras_dir.join("release").join(tool.to_string());
+    crate::fs::copy_dir_all(release_templates_dir, project.src_root()).await?;
+
+    let Some(ci) = project.ci() else {
+        warn!("The release extra adds no release jobs to projects without CI setup");
+        return Ok(());
+    };
+
+    let ci_templates_dir = extras_dir
+        .join("ci")
+        .join(ci.to_string())
+        .join(RELEASE_JOBS_DIR_NAME);
+    if !fs::try_exists(&ci_templates_dir).await? {
+        warn!("The {ci} CI provider does not support the release extra");
+        return Ok(());
+    }
+
+    // Providers without a separate release pipeline add the jobs to the CI pipeline.
+    let pipeline_path = ci_templates_dir.join(PIPELINE_FILE_NAME);
+    let project_pipeline_path = project.src_root().join(ci.release_pipeline_path());
+    let pipeline = match fs::try_exists(&pipeline_path).await? {
+        true => fs::read_to_string(pipeline_path).await?,
+        false => fs::read_to_string(&project_pipeline_path).await? + "\n",
+    };
+
+    let jobs_dir = ci_templates_dir.join(JOBS_DIR_NAME);
+    let pipeline = compose_pipeline(pipeline, &jobs_dir, ci, release_jobs(project, tool)).await?;
+
+    if let Some(dir) = project_pipeline_path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    fs::write(project_pipeline_path, pipeline).await?;
+
+    Ok(())
+}
+
+/// Lists the jobs of the project's release pipeline in order.
+fn release_jobs(project: &Project, tool: &ReleaseTool) -> Vec<PipelineJob> {
+    let mut jobs = vec![];
+    if *tool == ReleaseTool::ReleasePlz {
+        jobs.push(PipelineJob::new(&["release-plz"]));
+    }
+    jobs.push(PipelineJob::new(&["binaries"]).required_by("release extra".into()));
+    if project.has_extra(&ProjectExtra::Docker) {
+        jobs.push(
+            PipelineJob::new(&["docker"])
+                .required_by("publishing of Docker images by the release extra".into()),
+        );
+    }
+    jobs
+}
@@ -0,0 +1,95 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use tokio::fs;
+
+use crate::{
+    config::Config,
+    project::{
+        extras::{ci::CI, ProjectExtra},
+        Project,
+    },
+    Error,
+};
+
+/// The path of the Dependabot configuration file, relative to the project's root directory.
+pub const DEPENDABOT_FILE_PATH: &str = ".github/dependabot.yml";
+
+/// The name of the Renovate configuration file.
+pub const RENOVATE_FILE_NAME: &str = "renovate.json";
+
+/// Adds the configuration of a bot updating the project's dependencies.
+///
+/// Projects using GitHub Actions are configured for Dependabot, and other projects
+/// for Renovate. Both update the crates of the project, grouping the actix crates,
+/// as well as the base images of the Dockerfile and the actions of the CI pipeline
+/// if the project includes Docker or CI setup.
+///
+/// # Arguments
+///
+/// - `app_handle`: An instance of `AppHandle` used for accessing application configuration and resources.
+/// - `project`: The `Project` to which the configuration will be added.
+///
+/// # Returns
+///
+/// - `Ok(())` if the setup completes successfully.
+/// - `Err` if an error occurs during setup, such as file copy failures.
+pub async fn setup_updates(app_handle: &Config, project: &Project) -> crate::Result<()> {
+    let updates_templates_dir = app_handle.templates_dir().join("extras").join("updates");
+    let has_docker = project.has_extra(&ProjectExtra::Docker);
+
+    match project.ci() {
+        Some(CI::GitHub) => {
+            let dependabot_templates_dir = updates_templates_dir.join("dependabot");
+            let mut config =
+                fs::read_to_string(dependabot_templates_dir.join("dependabot.yml")).await?;
+
+            let ecosystems = ["cargo", "docker", "github-actions"]
+                .into_iter()
+                .filter(|ecosystem| *ecosystem != "docker" || has_docker);
+            for (index, ecosystem) in ecosystems.enumerate() {
+                if index > 0 {
+                    config.push('\n');
+                }
+                let path = dependabot_templates_dir
+                    .join("updates")
+                    .join(format!("{ecosystem}.yml"));
+                config.push_str(&fs::read_to_string(path).await?);
+            }
+
+            let dependabot_path = project.src_root().join(DEPENDABOT_FILE_PATH);
+            fs::create_dir_all(dependabot_path.parent().unwrap()).await?;
+            fs::write(dependabot_path, config).await?;
+        }
+        ci => {
+            let renovate_template_path = updates_templates_dir
+                .join("renovate")
+                .join(RENOVATE_FILE_NAME);
+            let content = fs::read_to_string(&renovate_template_path).await?;
+            let mut config: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_str(&content).map_err(|err| Error::InvalidExtraTemplate {
+                    path: renovate_template_path,
+                    message: err.to_string(),
+                })?;
+
+            // Renovate detects the files of other tools too, so only the files
+            // of the project's setup are updated.
+            let mut managers = vec!["cargo"];
+            if has_docker {
+                managers.push("dockerfile");
+            }
+            match ci {
+                Some(CI::GitLab) => managers.push("gitlabci"),
+                Some(CI::Forgejo) => managers.push("github-actions"),
+                Some(CI::Woodpecker) => managers.push("woodpecker"),
+                Some(CI::GitHub) | None => {}
+            }
+            config.insert("enabledManagers".into(), managers.into());
+
+            let content = serde_json::to_string_pretty(&config).unwrap() + "\n";
+            fs::write(project.src_root().join(RENOVATE_FILE_NAME), content).await?;
+        }
+    }
+
+    Ok(())
+}
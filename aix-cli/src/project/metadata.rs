@@ -0,0 +1,141 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+use crate::{
+    project::Project,
+    template::{archive::sha256_hex, TemplateProvenance},
+};
+
+/// The name of the file recording metadata at the root of a generated project.
+pub const PROJECT_FILE_NAME: &str = ".aix.toml";
+
+/// Metadata recorded in a generated project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ProjectMetadata {
+    /// The project as generated, including later changes to its extras.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<Project>,
+
+    /// Where the project template was obtained from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<TemplateProvenance>,
+
+    /// The SHA-256 digests of the files generated by aix, keyed by their path
+    /// relative to the project's root directory.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<PathBuf, String>,
+}
+
+impl ProjectMetadata {
+    /// Reads the metadata of the project located at `project_dir`.
+    ///
+    /// Returns `None` if the project has no metadata file.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidProjectMetadata` if the metadata cannot be parsed.
+    pub async fn load<P: AsRef<Path>>(project_dir: P) -> crate::Result<Option<Self>> {
+        let path = project_dir.as_ref().join(PROJECT_FILE_NAME);
+        if !fs::try_exists(&path).await? {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path).await?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|source| crate::Error::InvalidProjectMetadata {
+                path,
+                source: Box::new(source),
+            })
+    }
+
+    /// Records the digest of the current content of `file`, relative to `project_dir`.
+    pub async fn record_file<P: AsRef<Path>>(
+        &mut self,
+        project_dir: P,
+        file: &Path,
+    ) -> crate::Result<()> {
+        let content = fs::read(project_dir.as_ref().join(file)).await?;
+        self.files.insert(file.to_path_buf(), sha256_hex(&content));
+        Ok(())
+    }
+
+    /// Checks whether `content` matches the recorded digest of `file`.
+    ///
+    /// Returns `None` if no digest was recorded for `file`.
+    pub fn matches(&self, file: &Path, content: &[u8]) -> Option<bool> {
+        self.files
+            .get(file)
+            .map(|digest| *digest == sha256_hex(content))
+    }
+
+    /// Writes the metadata to the project located at `project_dir`.
+    pub async fn save<P: AsRef<Path>>(&self, project_dir: P) -> crate::Result<()> {
+        let content = toml::to_string_pretty(self).unwrap();
+        fs::write(project_dir.as_ref().join(PROJECT_FILE_NAME), content).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_project_metadata_save_and_load() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        assert!(ProjectMetadata::load(temp_dir.path()).await?.is_none());
+
+        let metadata = ProjectMetadata {
+            template: Some(TemplateProvenance {
+                name: "standalone".into(),
+                registry: Some("aix".into()),
+                source: "git@github.com:ekkolon/aix.git".into(),
+                branch: Some("main".into()),
+                commit: Some("0123456789abcdef".into()),
+                sha256: None,
+            }),
+            ..Default::default()
+        };
+        metadata.save(temp_dir.path()).await?;
+
+        let content = fs::read_to_string(temp_dir.path().join(PROJECT_FILE_NAME)).await?;
+        assert!(content.contains("[template]"));
+        assert!(!content.contains("sha256"));
+        assert!(!content.contains("[files]"));
+
+        let loaded = ProjectMetadata::load(temp_dir.path()).await?;
+        assert_eq!(loaded, Some(metadata));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_project_metadata_record_file() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        fs::create_dir_all(temp_dir.path().join(".github")).await?;
+        fs::write(temp_dir.path().join(".github/dependabot.yml"), "version: 2").await?;
+
+        let file = Path::new(".github/dependabot.yml");
+        let mut metadata = ProjectMetadata::default();
+        assert_eq!(metadata.matches(file, b"version: 2"), None);
+
+        metadata.record_file(temp_dir.path(), file).await?;
+        assert_eq!(metadata.matches(file, b"version: 2"), Some(true));
+        assert_eq!(metadata.matches(file, b"version: 3"), Some(false));
+
+        metadata.save(temp_dir.path()).await?;
+        let loaded = ProjectMetadata::load(temp_dir.path()).await?;
+        assert_eq!(loaded, Some(metadata));
+
+        Ok(())
+    }
+}
@@ -3,5 +3,9 @@
 
 mod builder;
 pub mod extras;
+mod metadata;
+mod update;
 
 pub use builder::*;
+pub use metadata::*;
+pub use update::*;
@@ -0,0 +1,272 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use diffy::{ConflictStyle, MergeOptions};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::{
+    config::{Config, SyncMode},
+    project::{extras, Project, ProjectMetadata, ProjectType, PROJECT_FILE_NAME},
+};
+
+/// The extension of the files containing the template's version of files that
+/// could not be merged (e.g. `Dockerfile.rej`).
+pub const REJECT_FILE_EXTENSION: &str = "rej";
+
+/// The changes made to the files of a project by updating it to newer templates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectUpdate {
+    /// The files that were added to the templates, and created.
+    pub created: Vec<PathBuf>,
+    /// The files that were not modified since the project was generated, and replaced.
+    pub updated: Vec<PathBuf>,
+    /// The files that were removed from the templates, and deleted.
+    pub removed: Vec<PathBuf>,
+    /// The modified files that the changes of the templates were merged into.
+    pub merged: Vec<PathBuf>,
+    /// The modified files whose changes conflict with the changes of the templates,
+    /// which contain conflict markers.
+    pub conflicted: Vec<PathBuf>,
+    /// The files that could not be merged, e.g. binary files or files deleted since
+    /// the project was generated, whose new version was written to a `.rej` file.
+    pub rejected: Vec<PathBuf>,
+    /// The modified files that were removed from the templates, which were left behind.
+    pub kept: Vec<PathBuf>,
+}
+
+impl ProjectUpdate {
+    /// Checks whether the update changed no files.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Updates `project` to the latest templates of the template store its template
+/// was pulled from.
+///
+/// The project is rendered from the template's commit recorded in its metadata,
+/// and from the latest commit, using the recorded project options and template
+/// variables. The changes between both are merged into the project's files:
+/// files that were not modified since the project was generated are replaced,
+/// and modified text files are merged line by line, with conflict markers where
+/// both changed the same lines.
+///
+/// Extras are provided by the app's template store. For templates of other
+/// registries, only the template itself is updated.
+///
+/// # Errors
+/// - Returns `crate::Error::TemplateNotUpdatable` if the project's template was
+///   not pulled from a Git repository, e.g. when generated from an archive.
+/// - Propagates errors from syncing the template store or rendering the project.
+pub async fn update_project(
+    app_handle: &Config,
+    project: &Project,
+    sync_mode: SyncMode,
+) -> crate::Result<ProjectUpdate> {
+    let src_root = project.src_root();
+    let mut metadata = ProjectMetadata::load(src_root).await?.unwrap_or_default();
+
+    let provenance = metadata.template.clone().unwrap_or_default();
+    let (Some(registry), Some(commit)) = (&provenance.registry, &provenance.commit) else {
+        return Err(crate::Error::TemplateNotUpdatable {
+            name: provenance.name,
+        });
+    };
+
+    let store = app_handle.registry(registry).await?;
+    let uses_app_store = store.registry_name() == app_handle.registry_name();
+
+    let mut _locks = vec![];
+    for store in [Some(&store), (!uses_app_store).then_some(app_handle)]
+        .into_iter()
+        .flatten()
+    {
+        store.ensure_store(sync_mode).await?;
+        _locks.push(store.lock_store_shared().await?);
+    }
+
+    let template = store.template(&provenance.name).await?;
+    let latest = template.provenance().cloned();
+    if latest.as_ref().and_then(|latest| latest.commit.as_ref()) == Some(commit) {
+        return Ok(ProjectUpdate::default());
+    }
+
+    // Extras of templates of other registries are rendered from the same templates,
+    // as the version they were generated from is not recorded.
+    let old_templates_dir = tempfile::tempdir()?;
+    store
+        .export_templates(commit, old_templates_dir.path())
+        .await?;
+    let old_store = store.clone().with_templates_dir(old_templates_dir.path());
+    let old_extras_store = match uses_app_store {
+        true => &old_store,
+        false => app_handle,
+    };
+
+    // Variables added to the template use their defaults.
+    let mut new_project = project.clone();
+    for (name, variable) in template.variables().await? {
+        if let Some(default) = variable.default {
+            new_project.variables.entry(name).or_insert(default);
+        }
+    }
+
+    let old_dir = tempfile::tempdir()?;
+    let new_dir = tempfile::tempdir()?;
+    let template_name = &provenance.name;
+    render_project(
+        &old_store,
+        old_extras_store,
+        template_name,
+        project,
+        old_dir.path(),
+    )
+    .await?;
+    render_project(
+        &store,
+        app_handle,
+        template_name,
+        &new_project,
+        new_dir.path(),
+    )
+    .await?;
+
+    let mut files = crate::fs::list_files(old_dir.path()).await?;
+    files.extend(crate::fs::list_files(new_dir.path()).await?);
+    files.sort();
+    files.dedup();
+
+    let mut update = ProjectUpdate::default();
+    for file in files {
+        if file == Path::new(PROJECT_FILE_NAME) {
+            continue;
+        }
+
+        let base = crate::fs::read_if_exists(&old_dir.path().join(&file)).await?;
+        let new = crate::fs::read_if_exists(&new_dir.path().join(&file)).await?;
+        let path = src_root.join(&file);
+        let current = crate::fs::read_if_exists(&path).await?;
+
+        match &new {
+            Some(_) => metadata.record_file(new_dir.path(), &file).await?,
+            None => {
+                metadata.files.remove(&file);
+            }
+        }
+
+        // Files the templates did not change, or that already match the templates,
+        // are left as is.
+        if new == base || current == new {
+            continue;
+        }
+
+        let unmodified = current == base;
+        match (current, new) {
+            (_, Some(_)) if unmodified => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).await?;
+                }
+                fs::copy(new_dir.path().join(&file), &path).await?;
+                match base {
+                    Some(_) => update.updated.push(file),
+                    None => update.created.push(file),
+                }
+            }
+            (Some(_), None) if unmodified => {
+                fs::remove_file(&path).await?;
+                crate::fs::remove_empty_dirs(src_root, &path).await?;
+                update.removed.push(file);
+            }
+            (Some(_), None) => update.kept.push(file),
+            (Some(current), Some(new)) => {
+                let base = base.unwrap_or_default();
+                match merge(&base, &current, &new) {
+                    Some(Ok(merged)) => {
+                        fs::write(&path, merged).await?;
+                        update.merged.push(file);
+                    }
+                    Some(Err(conflicted)) => {
+                        fs::write(&path, conflicted).await?;
+                        update.conflicted.push(file);
+                    }
+                    None => {
+                        fs::write(reject_path(&path), new).await?;
+                        update.rejected.push(file);
+                    }
+                }
+            }
+            // Files deleted since the project was generated are not recreated.
+            (None, Some(new)) => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).await?;
+                }
+                fs::write(reject_path(&path), new).await?;
+                update.rejected.push(file);
+            }
+            (None, None) => {}
+        }
+    }
+
+    metadata.project = Some(new_project);
+    metadata.template = latest;
+    metadata.save(src_root).await?;
+
+    Ok(update)
+}
+
+/// Renders `project` from the template named `template_name` of `template_store`
+/// and the extras of `extras_store` in `dir`, with the template variables replaced.
+async fn render_project(
+    template_store: &Config,
+    extras_store: &Config,
+    template_name: &str,
+    project: &Project,
+    dir: &Path,
+) -> crate::Result<()> {
+    let mut project = project.clone();
+    project.src_root = dir.to_path_buf();
+
+    let template = template_store.template(template_name).await?;
+    template.copy_to(dir).await?;
+
+    // Like when generating the project, the workspace template of the app's
+    // template store uses the standalone template for the initial crate member.
+    let typ = *project.typ();
+    let is_typ_template = template_store.registry_name() == extras_store.registry_name()
+        && template_name == typ.to_string();
+    if is_typ_template && typ == ProjectType::Workspace {
+        let member = extras_store
+            .template(&ProjectType::Standalone.to_string())
+            .await?;
+        member.copy_to(dir.join(project.name())).await?;
+    }
+
+    extras::setup_extras(extras_store, &project).await?;
+    project.replace_template_vars().await?;
+    Ok(())
+}
+
+/// Merges the changes from `base` to `new` into `current` line by line.
+///
+/// Returns `None` if any of the files is not a text file, and `Err` with the
+/// merged content including conflict markers if the changes conflict.
+fn merge(base: &[u8], current: &[u8], new: &[u8]) -> Option<Result<String, String>> {
+    let base = std::str::from_utf8(base).ok()?;
+    let current = std::str::from_utf8(current).ok()?;
+    let new = std::str::from_utf8(new).ok()?;
+
+    Some(
+        MergeOptions::new()
+            .set_conflict_style(ConflictStyle::Merge)
+            .merge(base, current, new),
+    )
+}
+
+/// Gets the path of the file containing the template's version of the file at `path`.
+fn reject_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(REJECT_FILE_EXTENSION);
+    path.with_file_name(file_name)
+}
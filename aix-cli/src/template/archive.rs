@@ -0,0 +1,410 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::Display,
+    io::{Cursor, Read},
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+use tokio::fs;
+
+use crate::{Error, Result};
+
+/// The location of a template archive, either a local file or an HTTP(S) URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveSource {
+    Path(PathBuf),
+    Url(String),
+}
+
+impl Display for ArchiveSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Url(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+impl FromStr for ArchiveSource {
+    type Err = Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(Self::Url(s.into()))
+        } else {
+            Ok(Self::Path(s.into()))
+        }
+    }
+}
+
+impl ArchiveSource {
+    /// Derives a template name from the archive's file name, without its extension.
+    pub fn name(&self) -> String {
+        let file_name = match self {
+            Self::Path(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            Self::Url(url) => {
+                let path = url.split(['?', '#']).next().unwrap_or_default();
+                path.rsplit('/').next().unwrap_or_default().to_string()
+            }
+        };
+
+        [".tar.gz", ".tgz", ".zip"]
+            .iter()
+            .find_map(|ext| file_name.strip_suffix(ext))
+            .unwrap_or(&file_name)
+            .to_string()
+    }
+
+    /// Reads the archive from a local file or downloads it from its URL.
+    pub async fn fetch(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Path(path) => Ok(fs::read(path).await?),
+            Self::Url(url) => {
+                let url = url.clone();
+                tokio::task::spawn_blocking(move || download(&url))
+                    .await
+                    .unwrap()
+            }
+        }
+    }
+}
+
+/// The supported archive formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detects the format of an archive from its leading magic bytes.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x1f, 0x8b, ..] => Some(Self::TarGz),
+            [b'P', b'K', 0x03, 0x04, ..] => Some(Self::Zip),
+            _ => None,
+        }
+    }
+}
+
+/// Computes the hex-encoded SHA-256 digest of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Verifies that the SHA-256 digest of `bytes` matches the hex-encoded `expected` digest.
+///
+/// # Errors
+/// - Returns `crate::Error::ChecksumMismatch` if the digests differ.
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = sha256_hex(bytes);
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(Error::ChecksumMismatch {
+            expected: expected.trim().into(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Extracts a tar.gz or zip archive into the `dest` directory.
+///
+/// Entries are never written outside of `dest`: the archive is rejected if it
+/// contains absolute paths, paths with `..` components, or links.
+///
+/// # Errors
+/// - Returns `crate::Error::UnsupportedArchive` if the archive format is unknown.
+/// - Returns `crate::Error::UnsafeArchiveEntry` for entries that could escape `dest`.
+/// - Propagates I/O errors from decoding the archive or writing files.
+pub async fn extract(bytes: Vec<u8>, dest: &Path) -> Result<()> {
+    let format = ArchiveFormat::detect(&bytes).ok_or(Error::UnsupportedArchive)?;
+    let dest = dest.to_path_buf();
+
+    tokio::task::spawn_blocking(move || match format {
+        ArchiveFormat::TarGz => extract_tar_gz(&bytes, &dest),
+        ArchiveFormat::Zip => extract_zip(bytes, &dest),
+    })
+    .await
+    .unwrap()
+}
+
+/// Returns the directory containing the template within an extracted archive.
+///
+/// Archives commonly wrap their contents in a single top-level directory
+/// (e.g. `my-template-main/`), which is skipped.
+pub async fn template_root(dir: &Path) -> Result<PathBuf> {
+    let mut entries = fs::read_dir(dir).await?;
+    let mut dirs = vec![];
+    let mut has_files = false;
+
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_type().await?.is_dir() {
+            dirs.push(entry.path());
+        } else {
+            has_files = true;
+        }
+    }
+
+    match (dirs.len(), has_files) {
+        (1, false) => Ok(dirs.remove(0)),
+        _ => Ok(dir.to_path_buf()),
+    }
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let response = ureq::get(url).call().map_err(|err| Error::Download {
+        url: url.into(),
+        message: err.to_string(),
+    })?;
+
+    let mut bytes = vec![];
+    response.into_reader().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn extract_tar_gz(bytes: &[u8], dest: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_path_buf();
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(Error::UnsafeArchiveEntry { path });
+        }
+
+        let target = dest.join(safe_relative_path(&path)?);
+        if entry_type.is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry_type.is_file() {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::File::create(&target)?;
+            std::io::copy(&mut entry, &mut file)?;
+            set_mode(&target, entry.header().mode().ok())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn extract_zip(bytes: Vec<u8>, dest: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(zip_err)?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(zip_err)?;
+        let path = PathBuf::from(entry.name());
+
+        if entry.is_symlink() {
+            return Err(Error::UnsafeArchiveEntry { path });
+        }
+
+        let target = dest.join(safe_relative_path(&path)?);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::File::create(&target)?;
+            std::io::copy(&mut entry, &mut file)?;
+            set_mode(&target, entry.unix_mode())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the permission bits of an archive entry to the extracted file at `path`.
+///
+/// Only the read, write and executable bits are applied, so extracted files never
+/// become setuid or setgid.
+fn set_mode(path: &Path, mode: Option<u32>) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+
+    Ok(())
+}
+
+/// Ensures `path` is a relative path that stays within the directory it is joined to.
+fn safe_relative_path(path: &Path) -> Result<&Path> {
+    let is_safe = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+
+    if !is_safe || path.as_os_str().is_empty() {
+        return Err(Error::UnsafeArchiveEntry {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(path)
+}
+
+fn zip_err(err: zip::result::ZipError) -> Error {
+    match err {
+        zip::result::ZipError::Io(err) => Error::Io(err),
+        err => Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn tar_gz(entries: &[(&str, &str)]) -> Vec<u8> {
+        let entries: Vec<_> = entries.iter().map(|(p, c)| (*p, *c, 0o644)).collect();
+        tar_gz_with_modes(&entries)
+    }
+
+    fn tar_gz_with_modes(entries: &[(&str, &str, u32)]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        for (path, content, mode) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(*mode);
+            // Write the name directly to bypass the path validation of the builder.
+            let name = &mut header.as_old_mut().name;
+            name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_cksum();
+            builder.append(&header, content.as_bytes()).unwrap();
+        }
+
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn zip(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(vec![]));
+        for (path, content) in entries {
+            writer
+                .start_file(*path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_archive_source_from_str() {
+        assert_eq!(
+            "https://example.com/t.zip"
+                .parse::<ArchiveSource>()
+                .unwrap(),
+            ArchiveSource::Url("https://example.com/t.zip".into())
+        );
+        assert_eq!(
+            "./t.tar.gz".parse::<ArchiveSource>().unwrap(),
+            ArchiveSource::Path("./t.tar.gz".into())
+        );
+    }
+
+    #[test]
+    fn test_archive_source_name() {
+        let source = ArchiveSource::Url("https://example.com/x/api.zip?token=1".into());
+        assert_eq!(source.name(), "api");
+
+        let source = ArchiveSource::Path("/tmp/templates/api.tar.gz".into());
+        assert_eq!(source.name(), "api");
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_sha256(b"hello", digest).is_ok());
+        assert!(verify_sha256(b"hello", &digest.to_uppercase()).is_ok());
+        assert!(matches!(
+            verify_sha256(b"hello!", digest),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_extract_tar_gz() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let bytes = tar_gz(&[("api/Cargo.toml", "[package]"), ("api/src/main.rs", "")]);
+
+        extract(bytes, temp_dir.path()).await?;
+
+        let content = fs::read_to_string(temp_dir.path().join("api/Cargo.toml")).await?;
+        assert_eq!(content, "[package]");
+        assert!(temp_dir.path().join("api/src/main.rs").is_file());
+        assert_eq!(
+            template_root(temp_dir.path()).await?,
+            temp_dir.path().join("api")
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_extract_tar_gz_preserves_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir()?;
+        let bytes = tar_gz_with_modes(&[("dev.sh", "#!/bin/sh", 0o4755), ("README.md", "", 0o644)]);
+
+        extract(bytes, temp_dir.path()).await?;
+
+        let mode = |name| {
+            std::fs::metadata(temp_dir.path().join(name)).map(|m| m.permissions().mode() & 0o7777)
+        };
+        assert_eq!(mode("dev.sh")?, 0o755);
+        assert_eq!(mode("README.md")?, 0o644);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extract_zip() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let bytes = zip(&[("Cargo.toml", "[package]"), ("src/main.rs", "")]);
+
+        extract(bytes, temp_dir.path()).await?;
+
+        assert!(temp_dir.path().join("Cargo.toml").is_file());
+        assert!(temp_dir.path().join("src/main.rs").is_file());
+        assert_eq!(template_root(temp_dir.path()).await?, temp_dir.path());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extract_rejects_path_traversal() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let dest = temp_dir.path().join("dest");
+
+        let bytes = tar_gz(&[("../escape.txt", "")]);
+        let result = extract(bytes, &dest).await;
+        assert!(matches!(result, Err(Error::UnsafeArchiveEntry { .. })));
+
+        let bytes = tar_gz(&[("/tmp/escape.txt", "")]);
+        let result = extract(bytes, &dest).await;
+        assert!(matches!(result, Err(Error::UnsafeArchiveEntry { .. })));
+
+        assert!(!temp_dir.path().join("escape.txt").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_extract_unsupported_archive() -> Result<()> {
+        let temp_dir = tempdir()?;
+
+        let result = extract(b"plain text".to_vec(), temp_dir.path()).await;
+        assert!(matches!(result, Err(Error::UnsupportedArchive)));
+        Ok(())
+    }
+}
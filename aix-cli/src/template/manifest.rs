@@ -0,0 +1,169 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+use tokio::fs;
+
+/// The name of the manifest file located at the root of a template directory.
+pub const MANIFEST_FILE_NAME: &str = "template.toml";
+
+/// Metadata describing a project template.
+///
+/// The manifest is read from the `template.toml` file at the root of a template
+/// directory and is never copied into generated projects.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct TemplateManifest {
+    /// A short, human-readable description of the template.
+    pub description: Option<String>,
+
+    /// The variables used in the template files, keyed by name.
+    pub variables: BTreeMap<String, TemplateVariable>,
+
+    /// Patterns of files to mark executable in generated projects, relative to the
+    /// template root (e.g. `scripts/*.sh`).
+    ///
+    /// `*` matches any characters except `/`, and `**` matches any characters.
+    /// Files that are executable in the template are always kept executable.
+    pub executable: Vec<String>,
+}
+
+/// A variable that is interpolated into template files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct TemplateVariable {
+    /// A short, human-readable description of the variable.
+    pub description: Option<String>,
+
+    /// The value used if none is provided when generating a project.
+    pub default: Option<String>,
+}
+
+impl TemplateManifest {
+    /// Checks whether the file at `path`, relative to the template root, is
+    /// marked executable by one of the manifest's `executable` patterns.
+    pub fn is_executable(&self, path: &Path) -> bool {
+        let path: Vec<_> = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect();
+        let path = path.join("/");
+
+        self.executable
+            .iter()
+            .any(|pattern| glob_to_regex(pattern).is_match(&path))
+    }
+
+    /// Reads the manifest of the template located at `template_dir`.
+    ///
+    /// Returns the default manifest if the template has no manifest file.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::InvalidTemplateManifest` if the manifest cannot be parsed.
+    /// - Propagates I/O errors from reading the manifest file.
+    pub async fn load<P: AsRef<Path>>(template_dir: P) -> crate::Result<Self> {
+        let path = template_dir.as_ref().join(MANIFEST_FILE_NAME);
+        if !fs::try_exists(&path).await? {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path).await?;
+        toml::from_str(&content).map_err(|source| crate::Error::InvalidTemplateManifest {
+            path,
+            source: Box::new(source),
+        })
+    }
+}
+
+/// Converts a glob pattern into an anchored regular expression.
+fn glob_to_regex(pattern: &str) -> Regex {
+    let mut regex = String::from("^");
+    let mut chars = pattern.trim_start_matches("./").chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    Regex::new(&regex).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_load_manifest() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let manifest = r#"
+            description = "A template"
+
+            [variables.crate_name]
+            description = "The crate name"
+
+            [variables.rust_version]
+            default = "1.75"
+        "#;
+        fs::write(temp_dir.path().join(MANIFEST_FILE_NAME), manifest).await?;
+
+        let manifest = TemplateManifest::load(temp_dir.path()).await?;
+        assert_eq!(manifest.description.as_deref(), Some("A template"));
+        assert_eq!(manifest.variables.len(), 2);
+        assert_eq!(
+            manifest.variables["rust_version"].default.as_deref(),
+            Some("1.75")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_manifest() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+
+        let manifest = TemplateManifest::load(temp_dir.path()).await?;
+        assert_eq!(manifest, TemplateManifest::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_is_executable() {
+        let manifest = TemplateManifest {
+            executable: vec!["scripts/*.sh".into(), "hooks/**".into(), "run".into()],
+            ..Default::default()
+        };
+
+        assert!(manifest.is_executable(Path::new("scripts/dev.sh")));
+        assert!(manifest.is_executable(Path::new("hooks/git/pre-commit")));
+        assert!(manifest.is_executable(Path::new("run")));
+        assert!(!manifest.is_executable(Path::new("scripts/nested/dev.sh")));
+        assert!(!manifest.is_executable(Path::new("scripts/dev.shx")));
+        assert!(!manifest.is_executable(Path::new("src/run")));
+    }
+
+    #[tokio::test]
+    async fn test_load_manifest_unknown_field() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        fs::write(temp_dir.path().join(MANIFEST_FILE_NAME), "unknown = 1").await?;
+
+        let result = TemplateManifest::load(temp_dir.path()).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidTemplateManifest { .. })
+        ));
+
+        Ok(())
+    }
+}
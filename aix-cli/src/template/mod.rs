@@ -0,0 +1,230 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+pub mod archive;
+mod manifest;
+mod provenance;
+
+pub use manifest::*;
+pub use provenance::*;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+use crate::utils::interpolation::find_template_vars;
+
+/// A project template located in the local template store.
+#[derive(Debug, Clone)]
+pub struct Template {
+    name: String,
+    path: PathBuf,
+    manifest: TemplateManifest,
+    provenance: Option<TemplateProvenance>,
+}
+
+impl Template {
+    /// Loads the template named `name` located at `path`.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::TemplateNotFound` if `path` is not a directory.
+    /// - Returns `crate::Error::InvalidTemplateManifest` if the template manifest cannot be parsed.
+    pub async fn load<P: AsRef<Path>>(name: &str, path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return Err(crate::Error::TemplateNotFound { name: name.into() });
+        }
+
+        Ok(Self {
+            name: name.into(),
+            path: path.to_path_buf(),
+            manifest: TemplateManifest::load(path).await?,
+            provenance: None,
+        })
+    }
+
+    /// Records where the template was obtained from.
+    pub fn with_provenance(mut self, provenance: TemplateProvenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Gets the name of the template.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the directory the template is located in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the manifest of the template.
+    pub fn manifest(&self) -> &TemplateManifest {
+        &self.manifest
+    }
+
+    /// Gets where the template was obtained from, if known.
+    pub fn provenance(&self) -> Option<&TemplateProvenance> {
+        self.provenance.as_ref()
+    }
+
+    /// Gets the description of the template, if any.
+    pub fn description(&self) -> Option<&str> {
+        self.manifest.description.as_deref()
+    }
+
+    /// Lists the files of the template relative to its root directory, in sorted order.
+    ///
+    /// The template manifest is not included.
+    pub async fn files(&self) -> crate::Result<Vec<PathBuf>> {
+        let mut files = crate::fs::list_files(&self.path).await?;
+        files.retain(|file| file != Path::new(MANIFEST_FILE_NAME));
+        Ok(files)
+    }
+
+    /// Collects the variables of the template.
+    ///
+    /// This includes the variables declared in the template manifest as well as
+    /// all variables referenced in the template files.
+    pub async fn variables(&self) -> crate::Result<BTreeMap<String, TemplateVariable>> {
+        let mut variables = self.manifest.variables.clone();
+
+        for file in self.files().await? {
+            let Ok(content) = fs::read_to_string(self.path.join(file)).await else {
+                // Skip binary files.
+                continue;
+            };
+
+            for name in find_template_vars(&content) {
+                variables.entry(name).or_default();
+            }
+        }
+
+        Ok(variables)
+    }
+
+    /// Copies the template files to the `dest` directory, omitting the template manifest.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::UnsafeTemplateEntry` if the template contains symbolic
+    ///   links pointing outside of the template.
+    pub async fn copy_to<P: AsRef<Path>>(&self, dest: P) -> crate::Result<()> {
+        let dest = dest.as_ref();
+        let filter = |path: &Path| path != Path::new(MANIFEST_FILE_NAME);
+        crate::fs::copy_dir_filtered(&self.path, dest, &filter).await?;
+
+        // Mark the files listed in the manifest executable.
+        if !self.manifest.executable.is_empty() {
+            for file in self.files().await? {
+                if self.manifest.is_executable(&file) {
+                    crate::fs::set_executable(dest.join(file)).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn create_template(dir: &Path) -> crate::Result<Template> {
+        fs::create_dir_all(dir.join("src")).await?;
+        fs::write(dir.join("Cargo.toml"), "name = \"{{ crate_name }}\"").await?;
+        fs::write(dir.join("src/main.rs"), "// {{ author }}").await?;
+        fs::write(
+            dir.join(MANIFEST_FILE_NAME),
+            "[variables.crate_name]\ndescription = \"The crate name\"",
+        )
+        .await?;
+
+        Template::load("test", dir).await
+    }
+
+    #[tokio::test]
+    async fn test_template_files() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let template = create_template(temp_dir.path()).await?;
+
+        let files = template.files().await?;
+        assert_eq!(
+            files,
+            vec![PathBuf::from("Cargo.toml"), PathBuf::from("src/main.rs")]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_template_variables() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let template = create_template(temp_dir.path()).await?;
+
+        let variables = template.variables().await?;
+        assert_eq!(
+            variables.keys().collect::<Vec<_>>(),
+            vec!["author", "crate_name"]
+        );
+        assert_eq!(
+            variables["crate_name"].description.as_deref(),
+            Some("The crate name")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_template_copy_to() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        let template = create_template(temp_dir.path()).await?;
+
+        template.copy_to(dest_dir.path()).await?;
+
+        assert!(dest_dir.path().join("Cargo.toml").is_file());
+        assert!(dest_dir.path().join("src/main.rs").is_file());
+        assert!(!dest_dir.path().join(MANIFEST_FILE_NAME).exists());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_template_copy_to_marks_files_executable() -> crate::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        fs::create_dir_all(temp_dir.path().join("scripts")).await?;
+        fs::write(temp_dir.path().join("scripts/dev.sh"), "#!/bin/sh").await?;
+        fs::write(
+            temp_dir.path().join(MANIFEST_FILE_NAME),
+            "executable = [\"scripts/*.sh\"]",
+        )
+        .await?;
+
+        let template = Template::load("test", temp_dir.path()).await?;
+        template.copy_to(dest_dir.path()).await?;
+
+        let metadata = fs::metadata(dest_dir.path().join("scripts/dev.sh")).await?;
+        assert_ne!(metadata.permissions().mode() & 0o111, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_template_not_found() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+
+        let result = Template::load("missing", temp_dir.path().join("missing")).await;
+        assert!(matches!(result, Err(crate::Error::TemplateNotFound { .. })));
+
+        Ok(())
+    }
+}
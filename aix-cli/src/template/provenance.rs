@@ -0,0 +1,31 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// Records where a project template was obtained from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateProvenance {
+    /// The name of the template.
+    pub name: String,
+
+    /// The registry the template belongs to, if it was pulled from a registry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+
+    /// The URL of the Git repository or archive the template was obtained from.
+    pub source: String,
+
+    /// The branch the template was pulled from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// The id of the commit the template was pulled from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// The SHA-256 checksum of the archive the template was extracted from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
@@ -0,0 +1,142 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::Path;
+use tokio::fs;
+
+use crate::{Error, Result};
+
+/// The format of an answers file, determined by its file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnswersFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl AnswersFormat {
+    /// Determines the format of the answers file at `path` from its extension.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::UnsupportedAnswersFile` if the extension is not one
+    ///   of `json`, `toml`, `yaml` or `yml`.
+    pub fn detect(path: &Path) -> Result<Self> {
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("json") => Ok(Self::Json),
+            Some("toml") => Ok(Self::Toml),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            _ => Err(Error::UnsupportedAnswersFile {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+}
+
+/// Reads answers from the JSON, TOML or YAML file at `path`.
+///
+/// # Errors
+/// - Returns `crate::Error::UnsupportedAnswersFile` if the format of the file is not supported.
+/// - Returns `crate::Error::InvalidAnswers` if the file cannot be parsed.
+pub async fn load<T, P>(path: P) -> Result<T>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let format = AnswersFormat::detect(path)?;
+    let content = fs::read_to_string(path).await?;
+
+    let answers = match format {
+        AnswersFormat::Json => serde_json::from_str(&content).map_err(|err| err.to_string()),
+        AnswersFormat::Toml => toml::from_str(&content).map_err(|err| err.to_string()),
+        AnswersFormat::Yaml => serde_yaml::from_str(&content).map_err(|err| err.to_string()),
+    };
+
+    answers.map_err(|message| Error::InvalidAnswers {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Writes `answers` to the file at `path`, in the format given by its extension.
+///
+/// # Errors
+/// - Returns `crate::Error::UnsupportedAnswersFile` if the format of the file is not supported.
+pub async fn save<T, P>(path: P, answers: &T) -> Result<()>
+where
+    T: Serialize,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+
+    let content = match AnswersFormat::detect(path)? {
+        AnswersFormat::Json => serde_json::to_string_pretty(answers).unwrap() + "\n",
+        AnswersFormat::Toml => toml::to_string_pretty(answers).unwrap(),
+        AnswersFormat::Yaml => serde_yaml::to_string(answers).unwrap(),
+    };
+
+    fs::write(path, content).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    struct Answers {
+        name: String,
+        setup_docker: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rust_version: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_answers() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+        let answers = Answers {
+            name: "my_api".into(),
+            setup_docker: true,
+            rust_version: None,
+        };
+
+        for file in [
+            "answers.json",
+            "answers.toml",
+            "answers.yaml",
+            "answers.yml",
+        ] {
+            let path = temp_dir.path().join(file);
+            save(&path, &answers).await?;
+            assert_eq!(load::<Answers, _>(&path).await?, answers);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_invalid_answers() -> crate::Result<()> {
+        let temp_dir = tempdir()?;
+
+        let path = temp_dir.path().join("answers.json");
+        fs::write(
+            &path,
+            r#"{ "name": "my_api", "setupDocker": true, "extra": 1 }"#,
+        )
+        .await?;
+        let result = load::<Answers, _>(&path).await;
+        assert!(matches!(result, Err(Error::InvalidAnswers { .. })));
+
+        let path = temp_dir.path().join("answers.ini");
+        fs::write(&path, "").await?;
+        let result = load::<Answers, _>(&path).await;
+        assert!(matches!(result, Err(Error::UnsupportedAnswersFile { .. })));
+
+        Ok(())
+    }
+}
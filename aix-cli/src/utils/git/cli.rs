@@ -0,0 +1,210 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::{GitBackend, GitRepository};
+use crate::{Error, Result};
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// A [`GitBackend`] that shells out to the `git` binary.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CliBackend;
+
+impl GitBackend for CliBackend {
+    fn is_available(&self) -> bool {
+        is_git_installed()
+    }
+
+    fn sparse_clone(&self, repository: &GitRepository, destination: &Path) -> Result<()> {
+        if !self.is_available() {
+            return Err(Error::MissingGitInstallation);
+        }
+
+        clone(destination, &repository.url, &repository.branch, Some("."))?;
+        sparse_checkout_init_cone(destination)?;
+        sparse_checkout_set_path(destination, &repository.directory)?;
+        Ok(())
+    }
+
+    fn fetch(&self, repository: &GitRepository, checkout: &Path) -> Result<String> {
+        if !self.is_available() {
+            return Err(Error::MissingGitInstallation);
+        }
+
+        let refspec = match repository.branch.as_str() {
+            "" => "HEAD",
+            branch => branch,
+        };
+
+        run(Command::new("git")
+            .current_dir(checkout)
+            .args(["remote", "set-url", "origin"])
+            .arg(&repository.url))?;
+        run(Command::new("git")
+            .current_dir(checkout)
+            .args(["fetch", "--filter=blob:none", "origin"])
+            .arg(refspec))?;
+        rev_parse(checkout, "FETCH_HEAD")
+    }
+
+    fn checkout(&self, repository: &GitRepository, checkout: &Path, commit: &str) -> Result<()> {
+        if !self.is_available() {
+            return Err(Error::MissingGitInstallation);
+        }
+
+        sparse_checkout_set_path(checkout, &repository.directory)?;
+        run(Command::new("git")
+            .current_dir(checkout)
+            .args(["checkout", "--force", "--detach"])
+            .arg(commit))
+    }
+
+    fn head_commit(&self, checkout: &Path) -> Result<String> {
+        if !self.is_available() {
+            return Err(Error::MissingGitInstallation);
+        }
+
+        rev_parse(checkout, "HEAD")
+    }
+
+    fn export(
+        &self,
+        repository: &GitRepository,
+        checkout: &Path,
+        commit: &str,
+        destination: &Path,
+    ) -> Result<()> {
+        if !self.is_available() {
+            return Err(Error::MissingGitInstallation);
+        }
+
+        // Reading the directory's tree into a temporary index leaves the index
+        // and files of the checkout untouched.
+        let index_dir = tempfile::tempdir()?;
+        let index_file = index_dir.path().join("index");
+        std::fs::create_dir_all(destination)?;
+
+        run(Command::new("git")
+            .current_dir(checkout)
+            .env("GIT_INDEX_FILE", &index_file)
+            .arg("--work-tree")
+            .arg(destination)
+            .arg("read-tree")
+            .arg(format!("{commit}:{}", repository.directory)))?;
+        run(Command::new("git")
+            .current_dir(checkout)
+            .env("GIT_INDEX_FILE", &index_file)
+            .arg("--work-tree")
+            .arg(destination)
+            .args(["checkout-index", "--all"]))
+    }
+}
+
+/// Checks if Git is installed on the system.
+fn is_git_installed() -> bool {
+    let child = Command::new("git")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    match child {
+        Ok(mut child) => child.wait().map(|_| true).unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Clones the `branch` of a Git repository to a specified destination directory.
+///
+/// If `branch` is empty, the default branch of the remote repository is cloned.
+fn clone<P, D>(current_dir: P, origin: &str, branch: &str, destination: Option<D>) -> Result<()>
+where
+    P: AsRef<Path>,
+    D: AsRef<str>,
+{
+    let mut cmd = Command::new("git");
+    cmd.current_dir(current_dir)
+        .arg("clone")
+        .arg("--filter=blob:none")
+        .arg("--sparse");
+
+    if !branch.is_empty() {
+        cmd.arg("--branch").arg(branch);
+    }
+
+    cmd.arg(origin);
+
+    if let Some(dest) = destination {
+        cmd.arg(dest.as_ref());
+    }
+
+    run(&mut cmd)
+}
+
+/// Initializes sparse checkout in the current Git repository using the cone mode.
+fn sparse_checkout_init_cone<P: AsRef<Path>>(current_dir: P) -> Result<()> {
+    run(Command::new("git")
+        .current_dir(current_dir)
+        .arg("sparse-checkout")
+        .arg("init")
+        .arg("--cone"))
+}
+
+/// Configures the sparse checkout to include a specific path in the Git repository.
+fn sparse_checkout_set_path<P: AsRef<Path>>(current_dir: P, path: &str) -> Result<()> {
+    run(Command::new("git")
+        .current_dir(current_dir)
+        .arg("sparse-checkout")
+        .arg("set")
+        .arg(path))
+}
+
+/// Resolves `rev` to a commit id in the Git repository at `current_dir`.
+fn rev_parse<P: AsRef<Path>>(current_dir: P, rev: &str) -> Result<String> {
+    output(
+        Command::new("git")
+            .current_dir(current_dir)
+            .args(["rev-parse", "--verify"])
+            .arg(format!("{rev}^{{commit}}")),
+    )
+}
+
+/// Runs a Git command to completion, discarding its output.
+///
+/// # Errors
+/// - Returns `crate::Error::Git` if the command exits with a non-zero status.
+/// - Propagates I/O errors from spawning the command.
+fn run(cmd: &mut Command) -> Result<()> {
+    let status = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?
+        .wait()?;
+
+    if !status.success() {
+        return Err(Error::Git {
+            message: format!("`{cmd:?}` exited with {status}"),
+        });
+    }
+
+    Ok(())
+}
+
+/// Runs a Git command to completion and returns its trimmed standard output.
+///
+/// # Errors
+/// - Returns `crate::Error::Git` if the command exits with a non-zero status.
+/// - Propagates I/O errors from spawning the command.
+fn output(cmd: &mut Command) -> Result<String> {
+    let output = cmd.stderr(Stdio::null()).output()?;
+
+    if !output.status.success() {
+        return Err(Error::Git {
+            message: format!("`{cmd:?}` exited with {}", output.status),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,188 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::{GitBackend, GitRepository};
+use crate::{Error, Result};
+use gix::bstr::ByteSlice;
+use gix::refs::transaction::PreviousValue;
+use gix::remote::Direction;
+use std::{path::Path, sync::atomic::AtomicBool};
+
+/// A pure-Rust [`GitBackend`] built on top of gitoxide.
+///
+/// Instead of relying on Git's sparse checkout, this backend fetches the repository
+/// without checking out a worktree and writes the files of the sparse directory
+/// from the fetched commit tree.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GixBackend;
+
+impl GitBackend for GixBackend {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn sparse_clone(&self, repository: &GitRepository, destination: &Path) -> Result<()> {
+        let branch = (!repository.branch.is_empty()).then_some(repository.branch.as_str());
+        let mut prepare = gix::prepare_clone(repository.url.as_str(), destination)
+            .map_err(git_err)?
+            .with_ref_name(branch)
+            .map_err(git_err)?;
+        let (repo, _) = prepare
+            .fetch_only(gix::progress::Discard, &AtomicBool::new(false))
+            .map_err(git_err)?;
+
+        let commit = repo.head_id().map_err(git_err)?.to_string();
+        self.checkout(repository, destination, &commit)
+    }
+
+    fn fetch(&self, repository: &GitRepository, checkout: &Path) -> Result<String> {
+        let repo = open(checkout)?;
+
+        let (source, tracking) = match repository.branch.as_str() {
+            "" => ("HEAD".to_string(), "refs/remotes/origin/HEAD".to_string()),
+            branch => (
+                format!("refs/heads/{branch}"),
+                format!("refs/remotes/origin/{branch}"),
+            ),
+        };
+
+        let remote = repo
+            .remote_at(repository.url.as_str())
+            .map_err(git_err)?
+            .with_refspecs(
+                Some(format!("+{source}:{tracking}").as_str()),
+                Direction::Fetch,
+            )
+            .map_err(git_err)?;
+
+        remote
+            .connect(Direction::Fetch)
+            .map_err(git_err)?
+            .prepare_fetch(gix::progress::Discard, Default::default())
+            .map_err(git_err)?
+            .receive(gix::progress::Discard, &AtomicBool::new(false))
+            .map_err(git_err)?;
+
+        let commit = repo
+            .find_reference(tracking.as_str())
+            .map_err(git_err)?
+            .peel_to_id_in_place()
+            .map_err(git_err)?;
+
+        Ok(commit.to_string())
+    }
+
+    fn checkout(&self, repository: &GitRepository, checkout: &Path, commit: &str) -> Result<()> {
+        let repo = open(checkout)?;
+        let id = gix::ObjectId::from_hex(commit.as_bytes()).map_err(git_err)?;
+
+        let dest = checkout.join(&repository.directory);
+        if dest.exists() {
+            std::fs::remove_dir_all(&dest)?;
+        }
+
+        let subtree = directory_tree(&repo, repository, id)?;
+        write_tree(&subtree, &dest)?;
+
+        // Detach HEAD at the checked out commit.
+        repo.reference("HEAD", id, PreviousValue::Any, "checkout")
+            .map_err(git_err)?;
+
+        Ok(())
+    }
+
+    fn head_commit(&self, checkout: &Path) -> Result<String> {
+        let repo = open(checkout)?;
+        let commit = repo.head_id().map_err(git_err)?;
+        Ok(commit.to_string())
+    }
+
+    fn export(
+        &self,
+        repository: &GitRepository,
+        checkout: &Path,
+        commit: &str,
+        destination: &Path,
+    ) -> Result<()> {
+        let repo = open(checkout)?;
+        let id = gix::ObjectId::from_hex(commit.as_bytes()).map_err(git_err)?;
+
+        let subtree = directory_tree(&repo, repository, id)?;
+        write_tree(&subtree, destination)
+    }
+}
+
+/// Finds the tree of `repository.directory` at the commit `id`.
+fn directory_tree<'repo>(
+    repo: &'repo gix::Repository,
+    repository: &GitRepository,
+    id: gix::ObjectId,
+) -> Result<gix::Tree<'repo>> {
+    let mut tree = repo
+        .find_commit(id)
+        .map_err(git_err)?
+        .tree()
+        .map_err(git_err)?;
+
+    let entry = tree
+        .peel_to_entry_by_path(&repository.directory)
+        .map_err(git_err)?
+        .ok_or_else(|| Error::Git {
+            message: format!("Path {} does not exist in repository", repository.directory),
+        })?;
+
+    Ok(entry.object().map_err(git_err)?.into_tree())
+}
+
+/// Opens the Git repository at `path`.
+///
+/// A committer identity is configured in-memory as updating references requires it
+/// for writing reflog entries.
+fn open(path: &Path) -> Result<gix::Repository> {
+    let options = gix::open::Options::default()
+        .config_overrides(["committer.name=aix", "committer.email=aix@localhost"]);
+    gix::open_opts(path, options).map_err(git_err)
+}
+
+/// Recursively writes the blobs of a tree object to the `dest` directory.
+fn write_tree(tree: &gix::Tree<'_>, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+
+    for entry in tree.iter() {
+        let entry = entry.map_err(git_err)?;
+        let path = dest.join(entry.filename().to_path().map_err(git_err)?);
+        let mode = entry.mode();
+
+        if mode.is_tree() {
+            let subtree = entry.object().map_err(git_err)?.into_tree();
+            write_tree(&subtree, &path)?;
+        } else if mode.is_blob() {
+            let blob = entry.object().map_err(git_err)?;
+            std::fs::write(&path, &blob.data)?;
+
+            #[cfg(unix)]
+            if mode.is_executable() {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+            }
+        } else if mode.is_link() {
+            // The blob of a symbolic link contains its target. Links are validated
+            // when templates are copied out of the checkout.
+            let blob = entry.object().map_err(git_err)?;
+            let target = blob.data.to_path().map_err(git_err)?;
+
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(target, path)?;
+            #[cfg(not(unix))]
+            let _ = target;
+        }
+    }
+
+    Ok(())
+}
+
+fn git_err<E: std::fmt::Display>(err: E) -> Error {
+    Error::Git {
+        message: err.to_string(),
+    }
+}
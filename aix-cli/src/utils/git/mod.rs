@@ -0,0 +1,88 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! This module provides an abstraction over the Git operations required to
+//! pull project templates from a remote repository.
+//!
+//! Git operations are performed by a [`GitBackend`]. By default, the [`CliBackend`]
+//! is used, which shells out to the `git` binary. When the `gix` cargo feature is
+//! enabled, a pure-Rust backend based on [gitoxide](https://github.com/Byron/gitoxide)
+//! is used instead, which does not require a Git installation.
+
+mod cli;
+#[cfg(feature = "gix")]
+mod gix;
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+
+pub use cli::CliBackend;
+#[cfg(feature = "gix")]
+pub use gix::GixBackend;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitRepository {
+    pub url: String,
+    /// The branch to pull, or the remote's default branch if empty.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub branch: String,
+    pub directory: String,
+    /// The SHA-256 digest the checksum manifest of the templates must match.
+    ///
+    /// Pinning the manifest independently of the repository detects templates
+    /// tampered with along with their manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksums_sha256: Option<String>,
+}
+
+/// A backend capable of performing the Git operations required by the template store.
+pub trait GitBackend: Send + Sync {
+    /// Checks whether the backend can be used on the current system.
+    fn is_available(&self) -> bool;
+
+    /// Clones `repository.branch` of `repository` into the `destination` directory,
+    /// checking out only the files located in `repository.directory`.
+    ///
+    /// If `repository.branch` is empty, the default branch of the remote is cloned.
+    ///
+    /// After a successful clone, the files of the sparse directory are located at
+    /// `destination/<repository.directory>`.
+    fn sparse_clone(&self, repository: &GitRepository, destination: &Path) -> Result<()>;
+
+    /// Fetches `repository.branch` of `repository` into the existing `checkout`
+    /// created by [`GitBackend::sparse_clone`], without modifying its files.
+    ///
+    /// Returns the id of the fetched commit.
+    fn fetch(&self, repository: &GitRepository, checkout: &Path) -> Result<String>;
+
+    /// Checks out `commit` in the existing `checkout`, updating only the files
+    /// located in `repository.directory`.
+    fn checkout(&self, repository: &GitRepository, checkout: &Path, commit: &str) -> Result<()>;
+
+    /// Returns the id of the commit currently checked out in `checkout`.
+    fn head_commit(&self, checkout: &Path) -> Result<String>;
+
+    /// Writes the files located in `repository.directory` at `commit` to the
+    /// `destination` directory, without modifying the existing `checkout`.
+    ///
+    /// `commit` must have been fetched into `checkout` before.
+    fn export(
+        &self,
+        repository: &GitRepository,
+        checkout: &Path,
+        commit: &str,
+        destination: &Path,
+    ) -> Result<()>;
+}
+
+/// Returns the default [`GitBackend`] for this build.
+///
+/// This is the [`GixBackend`] if the `gix` feature is enabled, otherwise the [`CliBackend`].
+pub fn default_backend() -> Arc<dyn GitBackend> {
+    #[cfg(feature = "gix")]
+    return Arc::new(GixBackend);
+
+    #[cfg(not(feature = "gix"))]
+    return Arc::new(CliBackend);
+}
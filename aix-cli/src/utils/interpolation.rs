@@ -88,11 +88,17 @@ fn replace_template_vars_all_recursive(
                 continue;
             }
 
-            if path.is_file() {
+            // Never follow symbolic links, which may point outside of the directory.
+            let file_type = entry.file_type().await?;
+            if file_type.is_symlink() {
+                continue;
+            }
+
+            if file_type.is_file() {
                 // Process the file
                 replace_template_vars(&path, &json_map).await?;
                 processed_files.push(path.display().to_string());
-            } else if path.is_dir() {
+            } else if file_type.is_dir() {
                 // Recursively process subdirectories
                 let new_exclude = exclude.clone(); // Clone the exclude list
                 let new_json_map = json_map.clone(); // Clone the json_map
@@ -132,8 +138,14 @@ async fn replace_template_vars(
     let content = interpolate_content(&content, json_map);
 
     // Write the modified content back to the file asynchronously
+    let permissions = fs::metadata(path).await?.permissions();
     let mut file = fs::File::create(path).await?;
     file.write_all(content.as_bytes()).await?;
+    // Tokio completes writes in the background, so wait for the write to finish.
+    file.flush().await?;
+
+    // Keep the permissions of the file, e.g. its executable bits.
+    fs::set_permissions(path, permissions).await?;
 
     Ok(())
 }
@@ -155,14 +167,33 @@ pub fn interpolate_content(content: &str, json_map: &HashMap<String, String>) ->
     // Replace all matches with the corresponding value from the json_map
     re.replace_all(content, |caps: &regex::Captures| {
         let key = &caps[1]; // Capture the variable name
+
+        // Unknown variables may belong to other tools (e.g. `{{version}}` of
+        // cargo-release), so they are left unchanged.
         json_map
             .get(key)
             .cloned()
-            .unwrap_or_else(|| format!("{{{{ {} }}}}", key)) // Replace or leave unchanged
+            .unwrap_or_else(|| caps[0].to_string())
     })
     .to_string()
 }
 
+/// Finds the names of all template variables in the form of `{{ var }}` in a string.
+///
+/// Each name is returned once, in order of first occurrence.
+pub fn find_template_vars(content: &str) -> Vec<String> {
+    let re = Regex::new(r"\{\{\s*(\w+)\s*\}\}").unwrap();
+
+    let mut names: Vec<String> = vec![];
+    for caps in re.captures_iter(content) {
+        let name = &caps[1];
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,37 +269,6 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_remove_files_except() -> crate::Result<()> {
-        let temp_dir = tempdir()?;
-        let file1 = temp_dir.path().join("keep.txt");
-        let file2 = temp_dir.path().join("remove.txt");
-
-        // Create files
-        File::create(&file1).await?;
-        File::create(&file2).await?;
-
-        // Remove files except `keep.txt`
-        remove_files_except(temp_dir.path(), &["keep.txt"]).await?;
-
-        assert!(file1.exists());
-        assert!(!file2.exists());
-
-        Ok(())
-    }
-
-    #[tokio::test]
-    async fn test_remove_no_files() -> crate::Result<()> {
-        let temp_dir = tempdir()?;
-
-        // Remove files except no files
-        remove_files_except(temp_dir.path(), &[]).await?;
-
-        assert!(temp_dir.path().is_dir());
-
-        Ok(())
-    }
-
     //#[tokio::test]
     //async fn test_replace_template_vars() -> crate::Result<()> {
     //    let temp_dir = tempdir()?;
@@ -303,6 +303,39 @@ mod tests {
         let interpolated_empty = interpolate_content(content, &json_map_empty);
         assert_eq!(interpolated_empty, "Hello {{ name }}!");
 
+        let interpolated_verbatim = interpolate_content("v{{version}}", &json_map);
+        assert_eq!(interpolated_verbatim, "v{{version}}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_template_vars() -> crate::Result<()> {
+        let content = "{{ name }} {{crate_name}} {{ name }} { name }";
+
+        let names = find_template_vars(content);
+        assert_eq!(names, vec!["name", "crate_name"]);
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_replace_template_vars_all_preserves_permissions() -> crate::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("dev.sh");
+        fs::write(&file_path, "echo {{ name }}").await?;
+        fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o755)).await?;
+
+        let json_map = HashMap::from([("name".to_string(), "Alice".to_string())]);
+        replace_template_vars_all(temp_dir.path(), vec![], json_map).await?;
+
+        let metadata = fs::metadata(&file_path).await?;
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+        assert_eq!(fs::read_to_string(&file_path).await?, "echo Alice");
+
         Ok(())
     }
 
@@ -1,5 +1,7 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+pub mod answers;
 pub mod git;
 pub mod interpolation;
+pub mod prompt;
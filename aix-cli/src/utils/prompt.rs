@@ -0,0 +1,156 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{
+    fmt::Display,
+    io::{BufRead, Stdin, Stdout, Write},
+    str::FromStr,
+};
+
+use crate::{Error, Result};
+
+/// Asks the user questions, reading answers from `input` and writing questions to `output`.
+///
+/// Invalid answers are reported and the question is asked again.
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl Prompter<std::io::StdinLock<'static>, Stdout> {
+    /// Creates a `Prompter` reading from stdin and writing to stdout.
+    pub fn stdio() -> Self {
+        Self::new(Stdin::lock(&std::io::stdin()), std::io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Creates a `Prompter` reading answers from `input` and writing questions to `output`.
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Asks for a line of text until it passes `validate`.
+    ///
+    /// An empty answer selects `default`, if any.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::PromptClosed` if the input ends before the question is answered.
+    pub fn text<F>(&mut self, question: &str, default: Option<&str>, validate: F) -> Result<String>
+    where
+        F: Fn(&str) -> std::result::Result<(), String>,
+    {
+        loop {
+            match default {
+                Some(default) => write!(self.output, "{question} [{default}]: ")?,
+                None => write!(self.output, "{question}: ")?,
+            }
+            self.output.flush()?;
+
+            let answer = self.read_line()?;
+            let answer = match (answer.as_str(), default) {
+                ("", Some(default)) => default.to_string(),
+                _ => answer,
+            };
+
+            match validate(&answer) {
+                Ok(()) => return Ok(answer),
+                Err(message) => writeln!(self.output, "  {message}")?,
+            }
+        }
+    }
+
+    /// Asks for a value of type `T` until the answer can be parsed.
+    ///
+    /// An empty answer selects `default`, if any.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::PromptClosed` if the input ends before the question is answered.
+    pub fn parse<T>(&mut self, question: &str, default: Option<&T>) -> Result<T>
+    where
+        T: FromStr + Display,
+        T::Err: Display,
+    {
+        let default = default.map(|default| default.to_string());
+        let answer = self.text(question, default.as_deref(), |answer| {
+            answer
+                .parse::<T>()
+                .map(|_| ())
+                .map_err(|err| err.to_string())
+        })?;
+
+        Ok(answer.parse().ok().unwrap())
+    }
+
+    /// Asks a yes or no question.
+    ///
+    /// An empty answer selects `default`.
+    ///
+    /// # Errors
+    /// - Returns `crate::Error::PromptClosed` if the input ends before the question is answered.
+    pub fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        let question = format!("{question} [{hint}]");
+
+        let answer = self.text(&question, None, |answer| {
+            match answer.to_ascii_lowercase().as_str() {
+                "" | "y" | "yes" | "n" | "no" => Ok(()),
+                _ => Err("Please answer yes or no".into()),
+            }
+        })?;
+
+        Ok(match answer.to_ascii_lowercase().as_str() {
+            "" => default,
+            answer => answer.starts_with('y'),
+        })
+    }
+
+    /// Reads a line of input without the trailing line break.
+    fn read_line(&mut self) -> Result<String> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(Error::PromptClosed);
+        }
+        Ok(line.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::ProjectType;
+
+    fn prompter(input: &str) -> Prompter<&[u8], Vec<u8>> {
+        Prompter::new(input.as_bytes(), vec![])
+    }
+
+    #[test]
+    fn test_prompt_text() -> crate::Result<()> {
+        let mut prompter = prompter("\n\nmy_api\n");
+        let not_empty = |answer: &str| match answer.is_empty() {
+            true => Err("The name must not be empty".to_string()),
+            false => Ok(()),
+        };
+
+        assert_eq!(prompter.text("Name", None, not_empty)?, "my_api");
+        let result = prompter.text("Name", Some("api"), not_empty);
+        assert!(matches!(result, Err(Error::PromptClosed)));
+
+        let output = String::from_utf8(prompter.output).unwrap();
+        let invalid = "Name:   The name must not be empty\n";
+        assert_eq!(output, format!("{invalid}{invalid}Name: Name [api]: "));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_parse_and_confirm() -> crate::Result<()> {
+        let mut prompter = prompter("library\n\nmaybe\nyes\n\n");
+
+        let kind = prompter.parse("Kind", Some(&ProjectType::Workspace))?;
+        assert_eq!(kind, ProjectType::Workspace);
+        assert!(prompter.confirm("Docker?", false)?);
+        assert!(prompter.confirm("CI?", true)?);
+
+        Ok(())
+    }
+}
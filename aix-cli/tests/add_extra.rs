@@ -0,0 +1,326 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use aix::config::{Config, SyncMode};
+use aix::git::CliBackend;
+use aix::project::extras::{self, ci::CI, ExtraChanges, ExtraRemoval, ProjectExtra};
+use aix::project::{Project, ProjectBuilder, ProjectMetadata};
+use aix::Error;
+use common::{config, RemoteRepository};
+use tempfile::{tempdir, TempDir};
+
+fn templates_remote() -> anyhow::Result<RemoteRepository> {
+    let remote = RemoteRepository::new()?;
+    remote.write(
+        "templates/standalone/Cargo.toml",
+        "[package]\nname = \"{{ crate_name }}\"\nrust-version = \"{{ rust_version }}\"\n",
+    )?;
+    remote.write(
+        "templates/extras/docker/Dockerfile",
+        "FROM rust\nRUN cargo build --bin {{ crate_name }}\n",
+    )?;
+    remote.write("templates/extras/ci/gitlab/pipeline.yml", "# gitlab\n")?;
+    remote.write("templates/extras/ci/github/pipeline.yml", "# github\n")?;
+    remote.write("templates/extras/ci/github/jobs/lint.yml", "lint:\n")?;
+    for job in ["lint", "test", "build", "docker"] {
+        remote.write(
+            &format!("templates/extras/ci/gitlab/jobs/{job}.yml"),
+            &format!("{job}:\n"),
+        )?;
+    }
+    remote.write(
+        "templates/extras/updates/renovate/renovate.json",
+        r#"{ "extends": ["config:recommended"] }"#,
+    )?;
+    remote.commit("Add templates")?;
+    Ok(remote)
+}
+
+/// Generates a project using GitLab CI in a temporary directory.
+async fn gitlab_project(config: &Config) -> anyhow::Result<TempDir> {
+    let project_dir = tempdir()?;
+    let project = ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitLab))
+        .rust_version("1.80")
+        .build()
+        .await?;
+    project.compile().await?;
+    Ok(project_dir)
+}
+
+/// Generates a project using GitLab CI and Docker in a temporary directory.
+async fn docker_project(config: &Config) -> anyhow::Result<TempDir> {
+    let project_dir = gitlab_project(config).await?;
+    let project = Project::detect(project_dir.path()).await?;
+    extras::add_extra(
+        config,
+        &project,
+        ProjectExtra::Docker,
+        SyncMode::Offline,
+        false,
+    )
+    .await?;
+    Ok(project_dir)
+}
+
+#[tokio::test]
+async fn test_detect_project() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = gitlab_project(&config).await?;
+
+    let project = Project::detect(project_dir.path()).await?;
+    assert_eq!(project.name(), "my_api");
+    assert_eq!(project.extras(), &vec![ProjectExtra::CI(CI::GitLab)]);
+
+    let not_a_project = tempdir()?;
+    assert!(matches!(
+        Project::detect(not_a_project.path()).await,
+        Err(Error::NotAProject { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_extra() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = gitlab_project(&config).await?;
+
+    let project = Project::detect(project_dir.path()).await?;
+    let changes = extras::add_extra(
+        &config,
+        &project,
+        ProjectExtra::Docker,
+        SyncMode::Offline,
+        false,
+    )
+    .await?;
+
+    assert_eq!(
+        changes,
+        ExtraChanges {
+            created: vec![PathBuf::from("Dockerfile")],
+            updated: vec![PathBuf::from(".gitlab-ci.yml")],
+            overwritten: vec![],
+        }
+    );
+    let dockerfile = std::fs::read_to_string(project_dir.path().join("Dockerfile"))?;
+    assert_eq!(dockerfile, "FROM rust\nRUN cargo build --bin my_api\n");
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".gitlab-ci.yml"))?;
+    assert!(pipeline.contains("docker:"));
+
+    let project = Project::detect(project_dir.path()).await?;
+    assert!(matches!(
+        extras::add_extra(
+            &config,
+            &project,
+            ProjectExtra::CI(CI::GitHub),
+            SyncMode::Offline,
+            false
+        )
+        .await,
+        Err(Error::ExtraExists { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_extra_with_modified_files() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = gitlab_project(&config).await?;
+
+    let pipeline_path = project_dir.path().join(".gitlab-ci.yml");
+    std::fs::write(&pipeline_path, "# modified\n")?;
+    let project = Project::detect(project_dir.path()).await?;
+
+    let result = extras::add_extra(
+        &config,
+        &project,
+        ProjectExtra::Docker,
+        SyncMode::Offline,
+        false,
+    )
+    .await;
+    match result {
+        Err(Error::ExtraConflicts { paths }) => {
+            assert_eq!(paths, vec![PathBuf::from(".gitlab-ci.yml")])
+        }
+        result => panic!("expected conflicts, got {result:?}"),
+    }
+    assert_eq!(std::fs::read_to_string(&pipeline_path)?, "# modified\n");
+    assert!(!project_dir.path().join("Dockerfile").exists());
+
+    let changes = extras::add_extra(
+        &config,
+        &project,
+        ProjectExtra::Docker,
+        SyncMode::Offline,
+        true,
+    )
+    .await?;
+    assert_eq!(changes.created, vec![PathBuf::from("Dockerfile")]);
+    assert_eq!(changes.overwritten, vec![PathBuf::from(".gitlab-ci.yml")]);
+    assert!(std::fs::read_to_string(&pipeline_path)?.contains("docker:"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_extra_records_files() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = gitlab_project(&config).await?;
+
+    let metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+    let files: Vec<_> = metadata.files.keys().cloned().collect();
+    assert_eq!(
+        files,
+        vec![PathBuf::from(".gitlab-ci.yml"), PathBuf::from("Cargo.toml")]
+    );
+
+    let project = Project::detect(project_dir.path()).await?;
+    extras::add_extra(
+        &config,
+        &project,
+        ProjectExtra::Docker,
+        SyncMode::Offline,
+        false,
+    )
+    .await?;
+
+    let loaded = Project::load(project_dir.path()).await?.unwrap();
+    assert_eq!(
+        loaded.extras(),
+        &vec![ProjectExtra::CI(CI::GitLab), ProjectExtra::Docker]
+    );
+
+    let metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+    let pipeline = std::fs::read(project_dir.path().join(".gitlab-ci.yml"))?;
+    assert_eq!(
+        metadata.matches(Path::new(".gitlab-ci.yml"), &pipeline),
+        Some(true)
+    );
+    assert!(metadata.files.contains_key(Path::new("Dockerfile")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remove_extra() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = docker_project(&config).await?;
+
+    let project = Project::detect(project_dir.path()).await?;
+    let removal =
+        extras::remove_extra(&config, &project, &ProjectExtra::Docker, SyncMode::Offline).await?;
+
+    assert_eq!(
+        removal,
+        ExtraRemoval {
+            removed: vec![PathBuf::from("Dockerfile")],
+            reverted: vec![PathBuf::from(".gitlab-ci.yml")],
+            kept: vec![],
+        }
+    );
+    assert!(!project_dir.path().join("Dockerfile").exists());
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".gitlab-ci.yml"))?;
+    assert!(!pipeline.contains("docker:"));
+    let metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+    assert!(!metadata.files.contains_key(Path::new("Dockerfile")));
+    let loaded = metadata.project.unwrap();
+    assert_eq!(loaded.extras(), &vec![ProjectExtra::CI(CI::GitLab)]);
+
+    let project = Project::detect(project_dir.path()).await?;
+    assert!(matches!(
+        extras::remove_extra(&config, &project, &ProjectExtra::Docker, SyncMode::Offline).await,
+        Err(Error::ExtraNotFound { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remove_extra_removes_empty_dirs() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = tempdir()?;
+    ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitHub))
+        .build()
+        .await?
+        .compile()
+        .await?;
+
+    let project = Project::detect(project_dir.path()).await?;
+    let removal = extras::remove_extra(
+        &config,
+        &project,
+        &ProjectExtra::CI(CI::default()),
+        SyncMode::Offline,
+    )
+    .await?;
+
+    assert_eq!(
+        removal.removed,
+        vec![PathBuf::from(".github/workflows/ci.yml")]
+    );
+    assert!(!project_dir.path().join(".github").exists());
+    assert!(project_dir.path().join("Cargo.toml").is_file());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remove_extra_keeps_modified_files() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    let project_dir = docker_project(&config).await?;
+
+    let dockerfile_path = project_dir.path().join("Dockerfile");
+    std::fs::write(&dockerfile_path, "FROM rust:slim\n")?;
+    let pipeline_path = project_dir.path().join(".gitlab-ci.yml");
+    std::fs::write(&pipeline_path, "# modified\n")?;
+
+    let project = Project::detect(project_dir.path()).await?;
+    let removal =
+        extras::remove_extra(&config, &project, &ProjectExtra::Docker, SyncMode::Offline).await?;
+
+    assert_eq!(
+        removal,
+        ExtraRemoval {
+            removed: vec![],
+            reverted: vec![],
+            kept: vec![PathBuf::from(".gitlab-ci.yml"), PathBuf::from("Dockerfile")],
+        }
+    );
+    assert_eq!(
+        std::fs::read_to_string(&dockerfile_path)?,
+        "FROM rust:slim\n"
+    );
+    assert_eq!(std::fs::read_to_string(&pipeline_path)?, "# modified\n");
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::{io::Write, path::Path, sync::Arc};
+
+use aix::config::{Config, RELATIVE_ARCHIVES_DIR};
+use aix::git::{CliBackend, GitRepository};
+use aix::project::{ProjectBuilder, ProjectMetadata};
+use aix::template::archive::{sha256_hex, ArchiveSource};
+use common::HttpServer;
+use tempfile::tempdir;
+
+const FILES: &[(&str, &str)] = &[
+    (
+        "api-main/template.toml",
+        "description = \"An API template\"",
+    ),
+    ("api-main/Cargo.toml", "name = \"{{ crate_name }}\""),
+    ("api-main/src/main.rs", "fn main() {}"),
+];
+
+fn tar_gz(files: &[(&str, &str)]) -> anyhow::Result<Vec<u8>> {
+    let encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, path, content.as_bytes())?;
+    }
+
+    Ok(builder.into_inner()?.finish()?)
+}
+
+fn zip(files: &[(&str, &str)]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+    for (path, content) in files {
+        writer.start_file(*path, zip::write::SimpleFileOptions::default())?;
+        writer.write_all(content.as_bytes())?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+/// A config without a reachable template repository, so that any attempt
+/// to sync the template store fails.
+fn offline_config(local_data_dir: &Path) -> Config {
+    let git_repo = GitRepository {
+        url: format!("file://{}", local_data_dir.join("missing.git").display()),
+        branch: "main".into(),
+        directory: "templates".into(),
+        checksums_sha256: None,
+    };
+    Config::with_local_data_dir("aix", local_data_dir, git_repo)
+        .with_git_backend(Arc::new(CliBackend))
+}
+
+#[tokio::test]
+async fn test_archive_template_from_path() -> anyhow::Result<()> {
+    let data_dir = tempdir()?;
+    let archive_dir = tempdir()?;
+    let config = offline_config(data_dir.path());
+
+    let archive_path = archive_dir.path().join("api.tar.gz");
+    std::fs::write(&archive_path, tar_gz(FILES)?)?;
+
+    let source = ArchiveSource::Path(archive_path);
+    let template = config.archive_template(&source, None).await?;
+
+    assert_eq!(template.name(), "api");
+    assert_eq!(template.description(), Some("An API template"));
+    assert_eq!(template.files().await?.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_template_from_url() -> anyhow::Result<()> {
+    let data_dir = tempdir()?;
+    let config = offline_config(data_dir.path());
+
+    let bytes = zip(FILES)?;
+    let sha256 = sha256_hex(&bytes);
+    let server = HttpServer::serve(vec![("/api.zip".into(), bytes)])?;
+
+    let source: ArchiveSource = server.url("/api.zip").parse()?;
+    let template = config.archive_template(&source, Some(&sha256)).await?;
+
+    assert_eq!(template.name(), "api");
+    assert_eq!(
+        template.path(),
+        data_dir
+            .path()
+            .join(RELATIVE_ARCHIVES_DIR)
+            .join(&sha256)
+            .join("api-main")
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_archive_template_concurrent() -> anyhow::Result<()> {
+    let data_dir = tempdir()?;
+    let archive_dir = tempdir()?;
+    let config = offline_config(data_dir.path());
+
+    let archive_path = archive_dir.path().join("api.tar.gz");
+    std::fs::write(&archive_path, tar_gz(FILES)?)?;
+
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let config = config.clone();
+            let source = ArchiveSource::Path(archive_path.clone());
+            tokio::spawn(async move { config.archive_template(&source, None).await })
+        })
+        .collect();
+    for task in tasks {
+        let template = task.await??;
+        assert_eq!(template.files().await?.len(), 2);
+    }
+
+    let archives_dir = data_dir.path().join(RELATIVE_ARCHIVES_DIR);
+    assert!(!archives_dir.join(".tmp").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_template_checksum_mismatch() -> anyhow::Result<()> {
+    let data_dir = tempdir()?;
+    let config = offline_config(data_dir.path());
+
+    let server = HttpServer::serve(vec![("/api.tar.gz".into(), tar_gz(FILES)?)])?;
+    let source: ArchiveSource = server.url("/api.tar.gz").parse()?;
+
+    let result = config
+        .archive_template(&source, Some(&"0".repeat(64)))
+        .await;
+    assert!(matches!(result, Err(aix::Error::ChecksumMismatch { .. })));
+    assert!(!data_dir.path().join(RELATIVE_ARCHIVES_DIR).exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_archive_template_download_fails() -> anyhow::Result<()> {
+    let data_dir = tempdir()?;
+    let config = offline_config(data_dir.path());
+
+    let server = HttpServer::serve(vec![])?;
+    let source: ArchiveSource = server.url("/missing.zip").parse()?;
+
+    let result = config.archive_template(&source, None).await;
+    assert!(matches!(result, Err(aix::Error::Download { .. })));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_build_project_from_archive_template() -> anyhow::Result<()> {
+    let data_dir = tempdir()?;
+    let archive_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = offline_config(data_dir.path());
+
+    let archive_path = archive_dir.path().join("api.tar.gz");
+    std::fs::write(&archive_path, tar_gz(FILES)?)?;
+    let source = ArchiveSource::Path(archive_path.clone());
+    let template = config.archive_template(&source, None).await?;
+
+    // The template store is never synced, as its repository is unreachable.
+    let project = ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .template(template)
+        .build()
+        .await?;
+    project.compile().await?;
+
+    let cargo_toml = std::fs::read_to_string(project_dir.path().join("Cargo.toml"))?;
+    assert_eq!(cargo_toml, "name = \"my_api\"");
+    assert!(project_dir.path().join("src/main.rs").is_file());
+    assert!(!project_dir.path().join("template.toml").exists());
+
+    let metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+    let provenance = metadata.template.unwrap();
+    assert_eq!(provenance.source, source.to_string());
+    assert_eq!(
+        provenance.sha256,
+        Some(sha256_hex(&std::fs::read(&archive_path)?))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#![allow(dead_code)]
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+
+use aix::config::Config;
+use aix::git::{GitBackend, GitRepository};
+use tempfile::TempDir;
+
+/// A bare Git repository in a temporary directory, served via a `file://` URL,
+/// together with a working copy used to push commits to it.
+pub struct RemoteRepository {
+    root: TempDir,
+}
+
+impl RemoteRepository {
+    /// Creates a bare repository with an initial, empty commit on `main`.
+    pub fn new() -> anyhow::Result<Self> {
+        let root = tempfile::tempdir()?;
+        let remote = Self { root };
+
+        git(
+            remote.root.path(),
+            &["init", "--bare", "-b", "main", "remote.git"],
+        )?;
+        git(remote.root.path(), &["clone", "remote.git", "work"])?;
+        git(&remote.work_dir(), &["checkout", "-b", "main"])?;
+        git(
+            &remote.work_dir(),
+            &["commit", "--allow-empty", "-m", "Initial commit"],
+        )?;
+        git(&remote.work_dir(), &["push", "-u", "origin", "main"])?;
+
+        Ok(remote)
+    }
+
+    pub fn url(&self) -> String {
+        format!("file://{}", self.root.path().join("remote.git").display())
+    }
+
+    pub fn work_dir(&self) -> PathBuf {
+        self.root.path().join("work")
+    }
+
+    /// Writes `contents` to `path` relative to the repository root.
+    pub fn write(&self, path: &str, contents: &str) -> anyhow::Result<()> {
+        let path = self.work_dir().join(path);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Creates a symbolic link at `path` relative to the repository root pointing to `target`.
+    #[cfg(unix)]
+    pub fn symlink(&self, path: &str, target: &str) -> anyhow::Result<()> {
+        let path = self.work_dir().join(path);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::os::unix::fs::symlink(target, path)?;
+        Ok(())
+    }
+
+    /// Removes the file at `path` relative to the repository root.
+    pub fn remove(&self, path: &str) -> anyhow::Result<()> {
+        git(&self.work_dir(), &["rm", "-q", path])
+    }
+
+    /// Commits all pending changes and pushes them to the current branch of the remote.
+    pub fn commit(&self, message: &str) -> anyhow::Result<()> {
+        git(&self.work_dir(), &["add", "-A"])?;
+        git(&self.work_dir(), &["commit", "-m", message])?;
+        git(&self.work_dir(), &["push", "origin", "HEAD"])
+    }
+
+    /// Returns the id of the commit checked out in the working copy.
+    pub fn head(&self) -> anyhow::Result<String> {
+        let output = Command::new("git")
+            .current_dir(self.work_dir())
+            .args(["rev-parse", "HEAD"])
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+
+    /// Checks out `branch`, creating it from the current branch if it does not exist yet.
+    pub fn checkout(&self, branch: &str) -> anyhow::Result<()> {
+        if git(&self.work_dir(), &["checkout", branch]).is_err() {
+            git(&self.work_dir(), &["checkout", "-b", branch])?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a `Config` that stores its data in `local_data_dir` and pulls templates
+/// from the `templates` directory of `branch` of the `remote` repository.
+pub fn config(
+    remote: &RemoteRepository,
+    branch: &str,
+    local_data_dir: &Path,
+    backend: Arc<dyn GitBackend>,
+) -> Config {
+    let git_repo = GitRepository {
+        url: remote.url(),
+        branch: branch.into(),
+        directory: "templates".into(),
+        checksums_sha256: None,
+    };
+
+    Config::with_local_data_dir("aix", local_data_dir, git_repo).with_git_backend(backend)
+}
+
+/// Returns all Git backends available in this build.
+pub fn backends() -> Vec<Arc<dyn GitBackend>> {
+    #[allow(unused_mut)]
+    let mut backends: Vec<Arc<dyn GitBackend>> = vec![Arc::new(aix::git::CliBackend)];
+
+    #[cfg(feature = "gix")]
+    backends.push(Arc::new(aix::git::GixBackend));
+
+    backends
+}
+
+fn git(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=aix", "-c", "user.email=aix@example.com"])
+        .args(args)
+        .output()?;
+
+    anyhow::ensure!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+/// A minimal HTTP server on a local port serving fixed responses by path.
+///
+/// Requests for unknown paths are answered with `404 Not Found`.
+pub struct HttpServer {
+    address: std::net::SocketAddr,
+}
+
+impl HttpServer {
+    /// Starts serving `routes`, a list of paths and their response bodies.
+    pub fn serve(routes: Vec<(String, Vec<u8>)>) -> anyhow::Result<Self> {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                // Drain the request headers.
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let response = routes.iter().find(|(route, _)| route == path);
+                let (status, body) = match response {
+                    Some((_, body)) => ("200 OK", body.as_slice()),
+                    None => ("404 Not Found", &b""[..]),
+                };
+
+                let header = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(header.as_bytes());
+                let _ = stream.write_all(body);
+            }
+        });
+
+        Ok(Self { address })
+    }
+
+    /// Gets the URL of `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.address)
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use aix::config::{Config, RefreshPolicy, SyncMode, SyncStatus};
+use aix::git::{CliBackend, GitRepository};
+use common::{config, RemoteRepository};
+use tempfile::tempdir;
+
+fn templates_remote() -> anyhow::Result<RemoteRepository> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.commit("Add templates")?;
+    Ok(remote)
+}
+
+fn unreachable_config(local_data_dir: &Path) -> Config {
+    let git_repo = GitRepository {
+        url: format!("file://{}", local_data_dir.join("missing.git").display()),
+        branch: "main".into(),
+        directory: "templates".into(),
+        checksums_sha256: None,
+    };
+    Config::with_local_data_dir("aix", local_data_dir, git_repo)
+        .with_git_backend(Arc::new(CliBackend))
+}
+
+fn cargo_toml(config: &Config) -> anyhow::Result<String> {
+    let path = config.templates_dir().join("standalone/Cargo.toml");
+    Ok(std::fs::read_to_string(path)?)
+}
+
+#[tokio::test]
+async fn test_sync_store_records_state() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    assert!(config.store_state().await?.is_none());
+    config.sync_store().await?;
+
+    let state = config.store_state().await?.unwrap();
+    assert_eq!(state.url, remote.url());
+    assert_eq!(state.branch, "main");
+    assert_eq!(state.commit, remote.head()?);
+    assert!(state.age() < Duration::from_secs(60));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_store_syncs_missing_store() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    config.ensure_store(SyncMode::Lazy).await?;
+    assert_eq!(cargo_toml(&config)?, "v1");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_store_offline_without_store() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let result = config.ensure_store(SyncMode::Offline).await;
+    assert!(matches!(result, Err(aix::Error::OfflineStoreMissing)));
+    assert!(!config.templates_dir().exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_store_moves_legacy_store() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    config.sync_store().await?;
+
+    // Versions without registries kept the store in the data directory.
+    for dir in [
+        config.templates_dir(),
+        config.checkout_dir(),
+        config.state_file(),
+    ] {
+        std::fs::rename(dir, data_dir.path().join(dir.file_name().unwrap()))?;
+    }
+    std::fs::remove_dir_all(data_dir.path().join("registries"))?;
+
+    config.ensure_store(SyncMode::Offline).await?;
+    assert_eq!(cargo_toml(&config)?, "v1");
+    assert!(!data_dir.path().join("templates").exists());
+    assert_eq!(config.sync_store().await?, SyncStatus::UpToDate);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_store_uses_cache_when_unreachable() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    config(&remote, "main", data_dir.path(), Arc::new(CliBackend))
+        .sync_store()
+        .await?;
+
+    let config = unreachable_config(data_dir.path())
+        .with_store_ttl(Duration::ZERO)
+        .with_refresh_policy(RefreshPolicy::Auto);
+
+    config.ensure_store(SyncMode::Offline).await?;
+    config.ensure_store(SyncMode::Refresh).await?;
+    config.ensure_store(SyncMode::Lazy).await?;
+    assert_eq!(cargo_toml(&config)?, "v1");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_store_refresh() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    config.sync_store().await?;
+
+    remote.write("templates/standalone/Cargo.toml", "v2")?;
+    remote.commit("Update templates")?;
+
+    config.ensure_store(SyncMode::Lazy).await?;
+    assert_eq!(cargo_toml(&config)?, "v1");
+
+    config.ensure_store(SyncMode::Refresh).await?;
+    assert_eq!(cargo_toml(&config)?, "v2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ensure_store_stale_policy() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend))
+        .with_store_ttl(Duration::ZERO);
+    config.sync_store().await?;
+
+    remote.write("templates/standalone/Cargo.toml", "v2")?;
+    remote.commit("Update templates")?;
+
+    // Stale templates are only reported by default.
+    config.ensure_store(SyncMode::Lazy).await?;
+    assert_eq!(cargo_toml(&config)?, "v1");
+
+    let config = config.with_refresh_policy(RefreshPolicy::Auto);
+    config.ensure_store(SyncMode::Lazy).await?;
+    assert_eq!(cargo_toml(&config)?, "v2");
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::sync::Arc;
+
+use aix::config::integrity::CHECKSUMS_FILE_NAME;
+use aix::config::{Config, UserConfig, UserConfigLayers};
+use aix::git::{CliBackend, GitRepository};
+use aix::project::{ProjectBuilder, ProjectMetadata};
+use aix::template::archive::sha256_hex;
+use common::{config, RemoteRepository};
+use tempfile::tempdir;
+
+fn checksums(files: &[(&str, &str)]) -> String {
+    files
+        .iter()
+        .map(|(path, contents)| format!("{}  {path}\n", sha256_hex(contents.as_bytes())))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_sync_store_verifies_checksums() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.write(
+        &format!("templates/{CHECKSUMS_FILE_NAME}"),
+        &checksums(&[("standalone/Cargo.toml", "v1")]),
+    )?;
+    remote.commit("Add templates")?;
+
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend))
+        .with_required_checksums(true);
+    config.sync_store().await?;
+
+    // Tamper with a template without updating the checksum manifest.
+    remote.write("templates/standalone/Cargo.toml", "v2")?;
+    remote.commit("Tamper with templates")?;
+
+    let result = config.sync_store().await;
+    assert!(matches!(
+        result,
+        Err(aix::Error::IntegrityCheckFailed { .. })
+    ));
+
+    // Retrying does not accept the rejected templates.
+    let result = config.sync_store().await;
+    assert!(matches!(
+        result,
+        Err(aix::Error::IntegrityCheckFailed { .. })
+    ));
+
+    // The verified templates are kept.
+    let cargo_toml = config.templates_dir().join("standalone/Cargo.toml");
+    assert_eq!(std::fs::read_to_string(cargo_toml)?, "v1");
+    assert!(!config.temp_dir().exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_verifies_pinned_manifest() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    let manifest = checksums(&[("standalone/Cargo.toml", "v1")]);
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.write(&format!("templates/{CHECKSUMS_FILE_NAME}"), &manifest)?;
+    remote.commit("Add templates")?;
+
+    let data_dir = tempdir()?;
+    let git_repo = GitRepository {
+        url: remote.url(),
+        branch: "main".into(),
+        directory: "templates".into(),
+        checksums_sha256: Some(sha256_hex(manifest.as_bytes())),
+    };
+    let config = Config::with_local_data_dir("aix", data_dir.path(), git_repo)
+        .with_git_backend(Arc::new(CliBackend));
+    config.sync_store().await?;
+
+    // Tamper with a template along with the checksum manifest.
+    remote.write("templates/standalone/Cargo.toml", "v2")?;
+    remote.write(
+        &format!("templates/{CHECKSUMS_FILE_NAME}"),
+        &checksums(&[("standalone/Cargo.toml", "v2")]),
+    )?;
+    remote.commit("Tamper with templates")?;
+
+    let result = config.sync_store().await;
+    assert!(matches!(
+        result,
+        Err(aix::Error::IntegrityCheckFailed { .. })
+    ));
+    let cargo_toml = config.templates_dir().join("standalone/Cargo.toml");
+    assert_eq!(std::fs::read_to_string(cargo_toml)?, "v1");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_requires_checksums() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.commit("Add templates")?;
+
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+    config
+        .clone()
+        .with_required_checksums(false)
+        .sync_store()
+        .await?;
+
+    remote.write("templates/standalone/Cargo.toml", "v2")?;
+    remote.commit("Update templates")?;
+
+    let result = config.with_required_checksums(true).sync_store().await;
+    assert!(matches!(
+        result,
+        Err(aix::Error::IntegrityCheckFailed { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_rejects_untrusted_source() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.commit("Add templates")?;
+
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend))
+        .with_trusted_sources(vec!["https://github.com/ekkolon/".into()]);
+
+    let result = config.sync_store().await;
+    assert!(matches!(result, Err(aix::Error::UntrustedSource { .. })));
+    assert!(!config.templates_dir().exists());
+
+    let config = config.with_trusted_sources(vec![remote.url()]);
+    config.sync_store().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_user_config_restricts_sources() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.commit("Add templates")?;
+
+    let mut user_config = UserConfig::default();
+    user_config.set("trusted_sources", "https://github.com/ekkolon")?;
+    user_config.set("require_checksums", "true")?;
+
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend)).with_user_config(
+        UserConfigLayers {
+            file: user_config,
+            ..Default::default()
+        },
+    );
+    assert!(config.require_checksums());
+
+    let result = config.sync_store().await;
+    assert!(matches!(result, Err(aix::Error::UntrustedSource { .. })));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_build_project_records_provenance() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "v1")?;
+    remote.commit("Add templates")?;
+
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .build()
+        .await?;
+
+    let metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+    let template = metadata.template.unwrap();
+    assert_eq!(template.name, "standalone");
+    assert_eq!(template.registry.as_deref(), Some("aix"));
+    assert_eq!(template.source, remote.url());
+    assert_eq!(template.branch.as_deref(), Some("main"));
+    assert_eq!(template.commit, Some(remote.head()?));
+
+    Ok(())
+}
@@ -0,0 +1,407 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::sync::Arc;
+
+use aix::config::{UserConfig, UserConfigLayers};
+use aix::git::CliBackend;
+use aix::project::extras::{
+    ci::{CiOption, CI},
+    release::ReleaseTool,
+    ProjectExtra,
+};
+use aix::project::{Project, ProjectBuilder};
+use common::{config, RemoteRepository};
+use tempfile::tempdir;
+
+fn templates_remote() -> anyhow::Result<RemoteRepository> {
+    let remote = RemoteRepository::new()?;
+    remote.write(
+        "templates/standalone/Cargo.toml",
+        "name = \"{{ crate_name }}\"",
+    )?;
+    remote.write("templates/extras/docker/Dockerfile", "FROM rust")?;
+    for ci in ["github", "gitlab", "forgejo", "woodpecker"] {
+        let dir = format!("templates/extras/ci/{ci}");
+        remote.write(&format!("{dir}/pipeline.yml"), &format!("# {ci}\n"))?;
+        for job in ["lint", "test", "build"] {
+            remote.write(&format!("{dir}/jobs/{job}.yml"), &format!("{job}:\n"))?;
+        }
+    }
+    remote.write(
+        "templates/extras/ci/github/jobs/msrv.yml",
+        "msrv:\n  toolchain: {{ rust_version }}\n",
+    )?;
+    remote.write(
+        "templates/extras/ci/github/jobs/test-multi-os.yml",
+        "test-multi-os:\n",
+    )?;
+    remote.write("templates/extras/ci/github/jobs/release.yml", "release:\n")?;
+    remote.write("templates/extras/ci/gitlab/jobs/docker.yml", "docker:\n")?;
+    remote.write(
+        "templates/extras/release/release-plz/release-plz.toml",
+        "git_tag_name = \"v{{ version }}\"",
+    )?;
+    remote.write(
+        "templates/extras/release/release-plz/CHANGELOG.md",
+        "## [0.1.0]",
+    )?;
+    remote.write(
+        "templates/extras/release/cargo-release/release.toml",
+        "tag-name = \"v{{version}}\"",
+    )?;
+    remote.write(
+        "templates/extras/release/cargo-release/CHANGELOG.md",
+        "## [0.1.0]",
+    )?;
+    remote.write(
+        "templates/extras/ci/github/release/pipeline.yml",
+        "# release\n",
+    )?;
+    for job in ["release-plz", "binaries", "docker"] {
+        remote.write(
+            &format!("templates/extras/ci/github/release/jobs/{job}.yml"),
+            &format!("{job}:\n"),
+        )?;
+    }
+    remote.write(
+        "templates/extras/ci/gitlab/release/jobs/binaries.yml",
+        "release-binaries:\n  script: tar czf {{ crate_name }}.tar.gz\n",
+    )?;
+    remote.write(
+        "templates/extras/updates/dependabot/dependabot.yml",
+        "version: 2\nupdates:\n",
+    )?;
+    for ecosystem in ["cargo", "docker", "github-actions"] {
+        remote.write(
+            &format!("templates/extras/updates/dependabot/updates/{ecosystem}.yml"),
+            &format!("  - package-ecosystem: {ecosystem}\n"),
+        )?;
+    }
+    remote.write(
+        "templates/extras/updates/renovate/renovate.json",
+        r#"{ "extends": ["config:recommended"] }"#,
+    )?;
+    remote.commit("Add templates")?;
+    Ok(remote)
+}
+
+#[tokio::test]
+async fn test_new_project_with_extras() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let project = ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitHub))
+        .setup_docker(true)
+        .build()
+        .await?;
+    project.compile().await?;
+
+    assert_eq!(project.ci(), Some(&CI::GitHub));
+    assert_eq!(Project::load(project_dir.path()).await?, Some(project));
+    let cargo_toml = std::fs::read_to_string(project_dir.path().join("Cargo.toml"))?;
+    assert_eq!(cargo_toml, "name = \"my_api\"");
+    assert!(project_dir.path().join("Dockerfile").is_file());
+    assert!(project_dir
+        .path()
+        .join(".github/workflows/ci.yml")
+        .is_file());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_with_configured_extras() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+
+    let mut user_config = UserConfig::default();
+    user_config.set("extras", "docker,ci")?;
+    user_config.set("ci", "github")?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend)).with_user_config(
+        UserConfigLayers {
+            file: user_config,
+            ..Default::default()
+        },
+    );
+
+    let project = ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_docker(false)
+        .build()
+        .await?;
+
+    assert_eq!(project.extras(), &vec![ProjectExtra::CI(CI::GitHub)]);
+    assert!(!project_dir.path().join("Dockerfile").exists());
+    assert!(project_dir
+        .path()
+        .join(".github/workflows/ci.yml")
+        .is_file());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_with_gitlab_ci() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    for setup_docker in [false, true] {
+        let project_dir = tempdir()?;
+        let project = ProjectBuilder::new(config.clone())
+            .name("my_api")
+            .src_root(project_dir.path())
+            .setup_ci(Some(CI::GitLab))
+            .setup_docker(setup_docker)
+            .rust_version("1.80")
+            .build()
+            .await?;
+        project.compile().await?;
+
+        let pipeline = std::fs::read_to_string(project_dir.path().join(".gitlab-ci.yml"))?;
+        assert!(pipeline.starts_with("# gitlab\nlint:\n\ntest:\n"));
+        assert_eq!(pipeline.contains("docker:"), setup_docker);
+        assert!(!project_dir.path().join(".github").exists());
+        assert!(!project_dir.path().join("pipeline.yml").exists());
+        assert!(!project_dir.path().join("jobs").exists());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_ci_providers() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let providers = [
+        (CI::GitHub, ".github/workflows/ci.yml"),
+        (CI::GitLab, ".gitlab-ci.yml"),
+        (CI::Forgejo, ".forgejo/workflows/ci.yml"),
+        (CI::Woodpecker, ".woodpecker.yml"),
+    ];
+
+    for (ci, pipeline) in providers {
+        let project_dir = tempdir()?;
+        ProjectBuilder::new(config.clone())
+            .name("my_api")
+            .src_root(project_dir.path())
+            .setup_ci(Some(ci.clone()))
+            .build()
+            .await?;
+
+        assert!(project_dir.path().join(pipeline).is_file(), "{ci}");
+        assert_eq!(ci.to_string().parse::<CI>()?, ci);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_with_ci_options() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let project = ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitHub))
+        .ci_options(vec![
+            CiOption::Release,
+            CiOption::Msrv,
+            CiOption::MultiOs,
+            CiOption::Audit,
+        ])
+        .rust_version("1.80")
+        .build()
+        .await?;
+    project.compile().await?;
+
+    // Jobs are composed in a fixed order, skipping those without a job file.
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".github/workflows/ci.yml"))?;
+    assert_eq!(
+        pipeline,
+        "# github\nlint:\n\ntest-multi-os:\n\nmsrv:\n  toolchain: 1.80\n\nbuild:\n\nrelease:\n"
+    );
+
+    // Providers without the job file of an option fall back to the default jobs.
+    let project_dir = tempdir()?;
+    ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::Woodpecker))
+        .ci_options(vec![CiOption::MultiOs, CiOption::Coverage])
+        .build()
+        .await?;
+
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".woodpecker.yml"))?;
+    assert_eq!(pipeline, "# woodpecker\nlint:\n\ntest:\n\nbuild:\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_with_static_ci_pipeline() -> anyhow::Result<()> {
+    let remote = RemoteRepository::new()?;
+    remote.write("templates/standalone/Cargo.toml", "")?;
+    remote.write(
+        "templates/extras/ci/github/.github/workflows/ci.yml",
+        "name: CI",
+    )?;
+    remote.commit("Add templates")?;
+
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitHub))
+        .ci_options(vec![CiOption::Msrv])
+        .build()
+        .await?;
+
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".github/workflows/ci.yml"))?;
+    assert_eq!(pipeline, "name: CI");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_with_release() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let project = ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitHub))
+        .ci_options(vec![CiOption::Release])
+        .setup_docker(true)
+        .setup_release(Some(ReleaseTool::ReleasePlz))
+        .build()
+        .await?;
+    project.compile().await?;
+
+    // Placeholders of the release tool are left to the release tool.
+    let release_plz_toml = std::fs::read_to_string(project_dir.path().join("release-plz.toml"))?;
+    assert_eq!(release_plz_toml, "git_tag_name = \"v{{ version }}\"");
+    assert!(project_dir.path().join("CHANGELOG.md").is_file());
+
+    let pipeline =
+        std::fs::read_to_string(project_dir.path().join(".github/workflows/release.yml"))?;
+    assert_eq!(
+        pipeline,
+        "# release\nrelease-plz:\n\nbinaries:\n\ndocker:\n"
+    );
+
+    // The release extra replaces the release job of the CI pipeline.
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".github/workflows/ci.yml"))?;
+    assert!(!pipeline.contains("release:"));
+
+    // Providers without a separate release pipeline add the jobs to the CI pipeline.
+    let project_dir = tempdir()?;
+    let project = ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitLab))
+        .setup_docker(true)
+        .setup_release(Some(ReleaseTool::CargoRelease))
+        .build()
+        .await?;
+    project.compile().await?;
+
+    let release_toml = std::fs::read_to_string(project_dir.path().join("release.toml"))?;
+    assert_eq!(release_toml, "tag-name = \"v{{version}}\"");
+    assert!(!project_dir.path().join("release-plz.toml").exists());
+
+    let pipeline = std::fs::read_to_string(project_dir.path().join(".gitlab-ci.yml"))?;
+    assert!(pipeline.ends_with("docker:\n\nrelease-binaries:\n  script: tar czf my_api.tar.gz\n"));
+
+    // Projects without CI setup only get the release tool's files.
+    let project_dir = tempdir()?;
+    ProjectBuilder::new(config)
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_release(Some(ReleaseTool::ReleasePlz))
+        .build()
+        .await?;
+
+    assert!(project_dir.path().join("CHANGELOG.md").is_file());
+    assert!(!project_dir.path().join(".github").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_project_with_updates() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    // Projects using GitHub Actions are configured for Dependabot.
+    let project_dir = tempdir()?;
+    ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .setup_ci(Some(CI::GitHub))
+        .setup_updates(true)
+        .build()
+        .await?;
+
+    let dependabot = std::fs::read_to_string(project_dir.path().join(".github/dependabot.yml"))?;
+    assert_eq!(
+        dependabot,
+        "version: 2\nupdates:\n  - package-ecosystem: cargo\n\n  - package-ecosystem: github-actions\n"
+    );
+    assert!(!project_dir.path().join("renovate.json").exists());
+
+    // Other projects are configured for Renovate.
+    let setups = [
+        (None, false, vec!["cargo"]),
+        (
+            Some(CI::GitLab),
+            true,
+            vec!["cargo", "dockerfile", "gitlabci"],
+        ),
+        (Some(CI::Woodpecker), false, vec!["cargo", "woodpecker"]),
+    ];
+    for (ci, setup_docker, managers) in setups {
+        let project_dir = tempdir()?;
+        ProjectBuilder::new(config.clone())
+            .name("my_api")
+            .src_root(project_dir.path())
+            .setup_ci(ci)
+            .setup_docker(setup_docker)
+            .setup_updates(true)
+            .build()
+            .await?;
+
+        let renovate = std::fs::read_to_string(project_dir.path().join("renovate.json"))?;
+        let renovate: serde_json::Value = serde_json::from_str(&renovate)?;
+        assert_eq!(renovate["enabledManagers"], serde_json::json!(managers));
+        assert_eq!(
+            renovate["extends"],
+            serde_json::json!(["config:recommended"])
+        );
+        assert!(!project_dir.path().join(".github").exists());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::sync::Arc;
+
+use aix::config::{TemplateRef, DEFAULT_REGISTRY, RELATIVE_REGISTRIES_DIR};
+use aix::git::{CliBackend, GitRepository};
+use aix::project::ProjectBuilder;
+use common::{config, RemoteRepository};
+use tempfile::tempdir;
+
+fn registry_remote(template: &str, contents: &str) -> anyhow::Result<RemoteRepository> {
+    let remote = RemoteRepository::new()?;
+    remote.write(&format!("templates/{template}/Cargo.toml"), contents)?;
+    remote.commit("Add templates")?;
+    Ok(remote)
+}
+
+fn git_repository(remote: &RemoteRepository) -> GitRepository {
+    GitRepository {
+        url: remote.url(),
+        branch: "main".into(),
+        directory: "templates".into(),
+        checksums_sha256: None,
+    }
+}
+
+#[tokio::test]
+async fn test_registry_stores_templates_separately() -> anyhow::Result<()> {
+    let aix_remote = registry_remote("standalone", "aix")?;
+    let company_remote = registry_remote("api", "company")?;
+    let data_dir = tempdir()?;
+    let config = config(&aix_remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    config
+        .add_registry("company", git_repository(&company_remote))
+        .await?;
+
+    let company = config.registry("company").await?;
+    company.sync_store().await?;
+    config.sync_store().await?;
+
+    let registries_dir = data_dir.path().join(RELATIVE_REGISTRIES_DIR);
+    assert_eq!(config.store_dir(), registries_dir.join(DEFAULT_REGISTRY));
+    assert_eq!(company.store_dir(), registries_dir.join("company"));
+
+    let api = std::fs::read_to_string(company.templates_dir().join("api/Cargo.toml"))?;
+    assert_eq!(api, "company");
+    assert!(!config.templates_dir().join("api").exists());
+    assert!(config.templates_dir().join("standalone").is_dir());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_registry_not_found() -> anyhow::Result<()> {
+    let remote = registry_remote("standalone", "aix")?;
+    let data_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let result = config.registry("company").await;
+    assert!(matches!(result, Err(aix::Error::RegistryNotFound { .. })));
+
+    let result = config.remove_registry(DEFAULT_REGISTRY).await;
+    assert!(matches!(result, Err(aix::Error::RegistryNotFound { .. })));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remove_registry_removes_store() -> anyhow::Result<()> {
+    let aix_remote = registry_remote("standalone", "aix")?;
+    let company_remote = registry_remote("api", "company")?;
+    let data_dir = tempdir()?;
+    let config = config(&aix_remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    config
+        .add_registry("company", git_repository(&company_remote))
+        .await?;
+    let company = config.registry("company").await?;
+    company.sync_store().await?;
+
+    config.remove_registry("company").await?;
+
+    assert!(!company.store_dir().exists());
+    assert!(config.registries().await?.get("company").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_build_project_from_registry_template() -> anyhow::Result<()> {
+    let aix_remote = registry_remote("standalone", "aix")?;
+    let company_remote = registry_remote("api", "name = \"{{ crate_name }}\"")?;
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&aix_remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    config
+        .add_registry("company", git_repository(&company_remote))
+        .await?;
+
+    let reference: TemplateRef = "company/api".parse()?;
+    let project = ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .template_ref(reference)
+        .build()
+        .await?;
+    project.compile().await?;
+
+    let cargo_toml = std::fs::read_to_string(project_dir.path().join("Cargo.toml"))?;
+    assert_eq!(cargo_toml, "name = \"my_api\"");
+
+    // The default registry is not needed without extras.
+    assert!(!config.templates_dir().exists());
+
+    Ok(())
+}
@@ -0,0 +1,326 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use aix::config::{SyncMode, SyncStatus};
+use common::{backends, config, RemoteRepository};
+use tempfile::tempdir;
+
+fn templates_remote() -> anyhow::Result<RemoteRepository> {
+    let remote = RemoteRepository::new()?;
+    remote.write("README.md", "# remote")?;
+    remote.write("src/lib.rs", "")?;
+    remote.write(
+        "templates/standalone/Cargo.toml",
+        "name = \"{{ crate_name }}\"",
+    )?;
+    remote.write("templates/extras/docker/Dockerfile", "FROM rust")?;
+    remote.commit("Add templates")?;
+    Ok(remote)
+}
+
+#[tokio::test]
+async fn test_sync_store_clones_templates() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+
+        config.sync_store().await?;
+
+        let templates_dir = config.templates_dir();
+        let cargo_toml = std::fs::read_to_string(templates_dir.join("standalone/Cargo.toml"))?;
+        assert_eq!(cargo_toml, "name = \"{{ crate_name }}\"");
+        assert!(templates_dir.join("extras/docker/Dockerfile").is_file());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_checks_out_templates_only() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+
+        config.sync_store().await?;
+
+        let templates_dir = config.templates_dir();
+        assert!(!templates_dir.join("README.md").exists());
+        assert!(!templates_dir.join("src").exists());
+        assert!(!templates_dir.join("templates").exists());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_resync() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+        config.sync_store().await?;
+
+        remote.write("templates/workspace/Cargo.toml", "[workspace]")?;
+        remote.remove("templates/extras/docker/Dockerfile")?;
+        remote.commit("Update templates")?;
+
+        config.sync_store().await?;
+
+        let templates_dir = config.templates_dir();
+        assert!(templates_dir.join("standalone/Cargo.toml").is_file());
+        assert!(templates_dir.join("workspace/Cargo.toml").is_file());
+        assert!(!templates_dir.join("extras/docker/Dockerfile").exists());
+
+        // Restore the remote for the next backend.
+        remote.write("templates/extras/docker/Dockerfile", "FROM rust")?;
+        remote.remove("templates/workspace/Cargo.toml")?;
+        remote.commit("Restore templates")?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_switch_branch() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    remote.checkout("next")?;
+    remote.write("templates/standalone/Cargo.toml", "name = \"next\"")?;
+    remote.commit("Update template on next")?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+
+        let main = config(&remote, "main", data_dir.path(), backend.clone());
+        main.sync_store().await?;
+        let cargo_toml = main.templates_dir().join("standalone/Cargo.toml");
+        assert_eq!(
+            std::fs::read_to_string(&cargo_toml)?,
+            "name = \"{{ crate_name }}\""
+        );
+
+        let next = config(&remote, "next", data_dir.path(), backend.clone());
+        next.sync_store().await?;
+        assert_eq!(std::fs::read_to_string(&cargo_toml)?, "name = \"next\"");
+
+        main.sync_store().await?;
+        assert_eq!(
+            std::fs::read_to_string(&cargo_toml)?,
+            "name = \"{{ crate_name }}\""
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_missing_branch() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "missing", data_dir.path(), backend);
+
+        let result = config.sync_store().await;
+        assert!(matches!(result, Err(aix::Error::Git { .. })));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_status() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+
+        assert_eq!(config.sync_store().await?, SyncStatus::Cloned);
+        assert!(config.checkout_dir().join(".git").is_dir());
+        assert_eq!(config.sync_store().await?, SyncStatus::UpToDate);
+
+        remote.write("templates/standalone/README.md", "# {{ crate_name }}")?;
+        remote.commit("Add readme")?;
+
+        assert_eq!(config.sync_store().await?, SyncStatus::Updated);
+        assert!(config
+            .templates_dir()
+            .join("standalone/README.md")
+            .is_file());
+        assert_eq!(config.sync_store().await?, SyncStatus::UpToDate);
+
+        remote.remove("templates/standalone/README.md")?;
+        remote.commit("Remove readme")?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_unchanged_remote_is_noop() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+        config.sync_store().await?;
+
+        let cargo_toml = config.templates_dir().join("standalone/Cargo.toml");
+        std::fs::write(&cargo_toml, "modified")?;
+
+        assert_eq!(config.sync_store().await?, SyncStatus::UpToDate);
+        assert_eq!(std::fs::read_to_string(&cargo_toml)?, "modified");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_store_removes_temp_dir() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+
+        // Simulate a leftover clone of a previous version.
+        std::fs::create_dir_all(config.temp_dir().join("templates"))?;
+
+        config.sync_store().await?;
+        assert!(!config.temp_dir().exists());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_sync_store_concurrent() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let configs: Vec<_> = (0..4)
+            .map(|_| config(&remote, "main", data_dir.path(), backend.clone()))
+            .collect();
+
+        let tasks: Vec<_> = configs
+            .iter()
+            .cloned()
+            .map(|config| tokio::spawn(async move { config.ensure_store(SyncMode::Lazy).await }))
+            .collect();
+        for task in tasks {
+            task.await??;
+        }
+
+        remote.write("templates/standalone/Cargo.toml", "name = \"concurrent\"")?;
+        remote.commit("Update template")?;
+
+        let tasks: Vec<_> = configs
+            .iter()
+            .cloned()
+            .map(|config| tokio::spawn(async move { config.sync_store().await }))
+            .collect();
+        let mut statuses = vec![];
+        for task in tasks {
+            statuses.push(task.await??);
+        }
+
+        // Only the first sync updates the templates, all others find them up to date.
+        let updated = statuses.iter().filter(|s| **s == SyncStatus::Updated);
+        assert_eq!(updated.count(), 1);
+
+        let templates_dir = configs[0].templates_dir();
+        let cargo_toml = std::fs::read_to_string(templates_dir.join("standalone/Cargo.toml"))?;
+        assert_eq!(cargo_toml, "name = \"concurrent\"");
+        assert!(!configs[0].temp_dir().exists());
+
+        remote.write(
+            "templates/standalone/Cargo.toml",
+            "name = \"{{ crate_name }}\"",
+        )?;
+        remote.commit("Restore template")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sync_store_preserves_inner_symlinks() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    remote.symlink(
+        "templates/standalone/Dockerfile",
+        "../extras/docker/Dockerfile",
+    )?;
+    remote.commit("Link Dockerfile")?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+        config.sync_store().await?;
+
+        let link = config.templates_dir().join("standalone/Dockerfile");
+        assert!(std::fs::symlink_metadata(&link)?.is_symlink());
+        assert_eq!(std::fs::read_to_string(&link)?, "FROM rust");
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sync_store_rejects_escaping_symlinks() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    remote.symlink("templates/standalone/README.md", "../../README.md")?;
+    remote.commit("Link README")?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+
+        let result = config.sync_store().await;
+        assert!(matches!(
+            result,
+            Err(aix::Error::UnsafeTemplateEntry { .. })
+        ));
+        assert!(!config.templates_dir().exists());
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sync_store_preserves_executable_files() -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let remote = templates_remote()?;
+    remote.write("templates/standalone/scripts/dev.sh", "#!/bin/sh")?;
+    let script = remote
+        .work_dir()
+        .join("templates/standalone/scripts/dev.sh");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))?;
+    remote.commit("Add dev script")?;
+
+    for backend in backends() {
+        let data_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+        config.sync_store().await?;
+
+        let template = config.template("standalone").await?;
+        let project_dir = tempdir()?;
+        template.copy_to(project_dir.path()).await?;
+
+        let metadata = std::fs::metadata(project_dir.path().join("scripts/dev.sh"))?;
+        assert_ne!(metadata.permissions().mode() & 0o111, 0);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+mod common;
+
+use std::{path::PathBuf, sync::Arc};
+
+use aix::config::SyncMode;
+use aix::git::CliBackend;
+use aix::project::extras::ci::CI;
+use aix::project::{self, Project, ProjectBuilder, ProjectMetadata, ProjectUpdate};
+use aix::Error;
+use common::{backends, config, RemoteRepository};
+use tempfile::tempdir;
+
+fn templates_remote() -> anyhow::Result<RemoteRepository> {
+    let remote = RemoteRepository::new()?;
+    remote.write(
+        "templates/standalone/Cargo.toml",
+        "[package]\nname = \"{{ crate_name }}\"\nedition = \"2021\"\n",
+    )?;
+    remote.write(
+        "templates/standalone/src/main.rs",
+        "use actix_web::App;\n\nfn main() {\n    App::new();\n}\n",
+    )?;
+    remote.write("templates/standalone/old.txt", "old\n")?;
+    remote.write("templates/standalone/notes.md", "# Notes\n")?;
+    remote.write(
+        "templates/extras/docker/Dockerfile",
+        "FROM rust:1.75\nRUN cargo build\n",
+    )?;
+    remote.write("templates/extras/ci/gitlab/pipeline.yml", "# gitlab\n")?;
+    for job in ["lint", "test", "build"] {
+        remote.write(
+            &format!("templates/extras/ci/gitlab/jobs/{job}.yml"),
+            &format!("{job}:\n"),
+        )?;
+    }
+    remote.commit("Add templates")?;
+    Ok(remote)
+}
+
+/// Improves the templates of `templates_remote`.
+fn update_templates(remote: &RemoteRepository) -> anyhow::Result<()> {
+    remote.write(
+        "templates/standalone/Cargo.toml",
+        "[package]\nname = \"{{ crate_name }}\"\nedition = \"2024\"\n",
+    )?;
+    remote.write(
+        "templates/standalone/src/main.rs",
+        "use actix_web::{App, HttpServer};\n\nfn main() {\n    App::new();\n}\n",
+    )?;
+    remote.remove("templates/standalone/old.txt")?;
+    remote.remove("templates/standalone/notes.md")?;
+    remote.write(
+        "templates/standalone/template.toml",
+        "[variables.port]\ndefault = \"8080\"\n",
+    )?;
+    remote.write("templates/standalone/.env", "PORT={{ port }}\n")?;
+    remote.write(
+        "templates/extras/docker/Dockerfile",
+        "FROM rust:1.80\nRUN cargo build\n",
+    )?;
+    remote.write(
+        "templates/extras/ci/gitlab/jobs/lint.yml",
+        "lint:\n  script: cargo clippy\n",
+    )?;
+    remote.commit("Improve templates")
+}
+
+#[tokio::test]
+async fn test_update_project() -> anyhow::Result<()> {
+    for backend in backends() {
+        let remote = templates_remote()?;
+        let data_dir = tempdir()?;
+        let project_dir = tempdir()?;
+        let config = config(&remote, "main", data_dir.path(), backend);
+
+        let project = ProjectBuilder::new(config.clone())
+            .name("my_api")
+            .src_root(project_dir.path())
+            .setup_ci(Some(CI::GitLab))
+            .setup_docker(true)
+            .build()
+            .await?;
+        project.compile().await?;
+
+        // Modify the project in ways that do and do not conflict with the templates.
+        let path = |file: &str| project_dir.path().join(file);
+        std::fs::write(
+            path("src/main.rs"),
+            "use actix_web::App;\n\nfn main() {\n    App::new();\n}\n\nmod routes;\n",
+        )?;
+        std::fs::write(path("Dockerfile"), "FROM rust:1.78\nRUN cargo build\n")?;
+        std::fs::write(path("notes.md"), "# Notes\n\nTODO\n")?;
+        std::fs::remove_file(path(".gitlab-ci.yml"))?;
+
+        update_templates(&remote)?;
+        let project = Project::load(project_dir.path()).await?.unwrap();
+        let update = project::update_project(&config, &project, SyncMode::Refresh).await?;
+
+        assert_eq!(
+            update,
+            ProjectUpdate {
+                created: vec![PathBuf::from(".env")],
+                updated: vec![PathBuf::from("Cargo.toml")],
+                removed: vec![PathBuf::from("old.txt")],
+                merged: vec![PathBuf::from("src/main.rs")],
+                conflicted: vec![PathBuf::from("Dockerfile")],
+                rejected: vec![PathBuf::from(".gitlab-ci.yml")],
+                kept: vec![PathBuf::from("notes.md")],
+            }
+        );
+
+        let read = |file: &str| std::fs::read_to_string(path(file));
+        assert_eq!(read(".env")?, "PORT=8080\n");
+        assert!(read("Cargo.toml")?.contains("edition = \"2024\""));
+        assert!(!path("old.txt").exists());
+        assert_eq!(
+            read("src/main.rs")?,
+            "use actix_web::{App, HttpServer};\n\nfn main() {\n    App::new();\n}\n\nmod routes;\n"
+        );
+        assert_eq!(
+            read("Dockerfile")?,
+            "<<<<<<< ours\nFROM rust:1.78\n=======\nFROM rust:1.80\n>>>>>>> theirs\nRUN cargo build\n"
+        );
+        assert!(!path(".gitlab-ci.yml").exists());
+        assert!(read(".gitlab-ci.yml.rej")?.contains("cargo clippy"));
+        assert_eq!(read("notes.md")?, "# Notes\n\nTODO\n");
+
+        // The project now records the latest templates.
+        let metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+        assert_eq!(metadata.template.unwrap().commit, Some(remote.head()?));
+        let project = metadata.project.unwrap();
+        assert_eq!(
+            project.variables.get("port").map(String::as_str),
+            Some("8080")
+        );
+
+        let project = Project::load(project_dir.path()).await?.unwrap();
+        let update = project::update_project(&config, &project, SyncMode::Refresh).await?;
+        assert!(update.is_empty());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_project_without_template_commit() -> anyhow::Result<()> {
+    let remote = templates_remote()?;
+    let data_dir = tempdir()?;
+    let project_dir = tempdir()?;
+    let config = config(&remote, "main", data_dir.path(), Arc::new(CliBackend));
+
+    let project = ProjectBuilder::new(config.clone())
+        .name("my_api")
+        .src_root(project_dir.path())
+        .build()
+        .await?;
+    project.compile().await?;
+
+    // Projects generated from template archives record no commit.
+    let mut metadata = ProjectMetadata::load(project_dir.path()).await?.unwrap();
+    metadata.template.as_mut().unwrap().commit = None;
+    metadata.save(project_dir.path()).await?;
+
+    assert!(matches!(
+        project::update_project(&config, &project, SyncMode::Offline).await,
+        Err(Error::TemplateNotUpdatable { .. })
+    ));
+
+    Ok(())
+}